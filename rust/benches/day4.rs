@@ -0,0 +1,38 @@
+//! Compares the day 4 hashing paths on the puzzle examples.
+//!
+//! Run with `cargo bench --bench day4`.
+
+use advent_of_code_2015::day4::{has_leading_zeros, mine, solve_part1_format, NonceHasher};
+use std::time::{Duration, Instant};
+
+const SECRETS: [&str; 2] = ["abcdef", "pqrstuv"];
+const ITERATIONS: usize = 3;
+
+fn fastest(mut f: impl FnMut() -> u64) -> (u64, Duration) {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            (f(), start.elapsed())
+        })
+        .min_by_key(|&(_, elapsed)| elapsed)
+        .unwrap()
+}
+
+fn main() {
+    for secret in SECRETS {
+        let (expected, format) = fastest(|| solve_part1_format(secret));
+        let (nonce, context) = fastest(|| {
+            let hasher = NonceHasher::new(secret);
+            (0u64..)
+                .find(|&nonce| has_leading_zeros(&hasher.digest(nonce), 5))
+                .unwrap()
+        });
+        assert_eq!(expected, nonce);
+        let (nonce, threaded) = fastest(|| mine(secret, 5, 4));
+        assert_eq!(expected, nonce);
+        println!(
+            "{secret}: nonce {expected}, format! {format:.3?}, reused context {context:.3?} ({:.2}x), 4 threads {threaded:.3?}",
+            format.as_secs_f64() / context.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::parse::ParseError;
+use crate::registry::Answer;
+
+/// Expected answers for a single day, as stored in an `answers/dayN.txt` file:
+///
+/// ```text
+/// # comments and blank lines are ignored
+/// part1 = 232
+/// part2 = "hxbxxyzz"
+/// ```
+///
+/// This is a `key = value` format of its own, not TOML: the only keys are `part1` and `part2`,
+/// values are an integer or a double-quoted string taken verbatim (no escapes), and a `#` outside
+/// the quotes starts a comment.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct AnswerKey {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+impl AnswerKey {
+    pub fn parse(day: u8, text: &str) -> Result<AnswerKey, ParseError> {
+        let mut key = AnswerKey::default();
+        for (idx, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |expected: &str| ParseError::new(day, idx + 1, 1, expected);
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("`part1 = <answer>` or `part2 = <answer>`"))?;
+            let slot = match name.trim() {
+                "part1" => &mut key.part1,
+                "part2" => &mut key.part2,
+                _ => return Err(error("`part1` or `part2`")),
+            };
+            *slot = Some(answer(value.trim()).ok_or_else(|| error("integer or quoted string"))?);
+        }
+        Ok(key)
+    }
+
+    pub fn get(&self, part: u8) -> Option<&Answer> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// `line` up to the first `#` outside a quoted string.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn answer(value: &str) -> Option<Answer> {
+    if let Some(text) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(Answer::String(text.to_string()))
+    } else {
+        value.parse().ok().map(Answer::Integer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::answers::AnswerKey;
+    use crate::parse::ParseError;
+    use crate::registry::Answer;
+
+    #[test]
+    fn parse_key() {
+        let key = AnswerKey::parse(11, "# day 11\npart1 = \"hxbxxyzz\"\n\npart2 = -42 # note\n");
+        assert_eq!(
+            Ok(AnswerKey {
+                part1: Some(Answer::String("hxbxxyzz".to_string())),
+                part2: Some(Answer::Integer(-42)),
+            }),
+            key
+        );
+    }
+
+    #[test]
+    fn hash_in_quotes() {
+        assert_eq!(
+            Ok(AnswerKey {
+                part1: Some(Answer::String("#a#b".to_string())),
+                part2: None,
+            }),
+            AnswerKey::parse(
+                1,
+                "part1 = \"#a#b\" # the # starts a comment here\n# part2 = 3"
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert_eq!(
+            Err(ParseError::new(1, 2, 1, "integer or quoted string")),
+            AnswerKey::parse(1, "part1 = 1\npart2 = abc")
+        );
+        assert_eq!(
+            Err(ParseError::new(1, 1, 1, "`part1` or `part2`")),
+            AnswerKey::parse(1, "part3 = 1")
+        );
+    }
+}
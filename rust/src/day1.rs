@@ -1,4 +1,9 @@
+use crate::parse::ParseError;
+use crate::stream::{invalid_byte, try_for_each_byte};
 use aoc_runner_derive::aoc;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+use std::ops::ControlFlow;
 
 #[aoc(day1, part1)]
 pub fn solve_part1(input: &str) -> i32 {
@@ -30,9 +35,182 @@ pub fn solve_part2(input: &str) -> usize {
         .unwrap()
 }
 
+/// Streaming [solve_part1], ignoring whitespace such as a trailing newline.
+pub fn floor_from_reader(reader: impl BufRead) -> io::Result<i32> {
+    let mut floor = 0;
+    let mut position = 0;
+    try_for_each_byte(reader, |byte| {
+        position += 1;
+        floor += direction(byte, position)?;
+        Ok(ControlFlow::<()>::Continue(()))
+    })?;
+    Ok(floor)
+}
+
+/// Streaming [solve_part2], `None` when the basement is never reached.
+pub fn basement_from_reader(reader: impl BufRead) -> io::Result<Option<usize>> {
+    let mut floor = 0;
+    let mut position = 0;
+    try_for_each_byte(reader, |byte| {
+        position += 1;
+        floor += direction(byte, position)?;
+        Ok(match floor {
+            level if level < 0 => ControlFlow::Break(position),
+            _ => ControlFlow::Continue(()),
+        })
+    })
+}
+
+fn direction(byte: u8, position: usize) -> io::Result<i32> {
+    match byte {
+        b'(' => Ok(1),
+        b')' => Ok(-1),
+        b if b.is_ascii_whitespace() => Ok(0),
+        b => Err(invalid_byte(b, position)),
+    }
+}
+
+/// [solve_part1] counting parentheses eight bytes at a time, ignoring any other byte.
+#[aoc(day1, part1, Swar)]
+pub fn solve_part1_swar(input: &str) -> i32 {
+    let (opens, closes) = count_parentheses(input.as_bytes());
+    opens as i32 - closes as i32
+}
+
+/// [solve_part2] skipping whole blocks that cannot reach the basement, `None` when the basement
+/// is never reached.
+///
+/// Starting a block on floor `n`, its lowest floor is at least `n` minus its closing
+/// parentheses, so only blocks where that bound drops below zero, or that hold anything but
+/// parentheses, are walked byte by byte. Like [solve_part2], that walk panics on the first
+/// unexpected byte before the basement.
+#[aoc(day1, part2, Blocks)]
+pub fn solve_part2_blocks(input: &str) -> Option<usize> {
+    let mut floor = 0i64;
+    for (idx, block) in input.as_bytes().chunks(BLOCK).enumerate() {
+        let (opens, closes) = count_parentheses(block);
+        if floor >= closes as i64 && opens + closes == block.len() {
+            floor += opens as i64 - closes as i64;
+            continue;
+        }
+        for (offset, &byte) in block.iter().enumerate() {
+            floor += match byte {
+                b'(' => 1,
+                b')' => -1,
+                _ => panic!("Unexpected input"),
+            };
+            if floor < 0 {
+                return Some(idx * BLOCK + offset + 1);
+            }
+        }
+    }
+    None
+}
+
+const BLOCK: usize = 256;
+
+const LOW_BITS: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+
+fn count_parentheses(bytes: &[u8]) -> (usize, usize) {
+    let mut chunks = bytes.chunks_exact(8);
+    let (mut opens, mut closes) = (0, 0);
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+        opens += count_byte(word, b'(');
+        closes += count_byte(word, b')');
+    }
+    for &byte in chunks.remainder() {
+        opens += (byte == b'(') as usize;
+        closes += (byte == b')') as usize;
+    }
+    (opens, closes)
+}
+
+/// Number of bytes of `word` equal to `byte`.
+fn count_byte(word: u64, byte: u8) -> usize {
+    let x = word ^ (u64::from(byte) * 0x0101_0101_0101_0101);
+    // High bit of each lane is set exactly when that lane is non-zero, without carries across lanes.
+    let non_zero = (((x & LOW_BITS) + LOW_BITS) | x) & HIGH_BITS;
+    8 - non_zero.count_ones() as usize
+}
+
+/// Everything about Santa's trip through the building, gathered in a single pass.
+///
+/// Positions are 1-based character positions as in the puzzle, position `0` being the start on
+/// floor `0`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct FloorTrace {
+    /// Floor after the last instruction.
+    pub floor: i32,
+    /// Lowest floor and the position it was first reached.
+    pub lowest: (i32, usize),
+    /// Highest floor and the position it was first reached.
+    pub highest: (i32, usize),
+    /// Position each visited floor was first reached.
+    pub first_visits: BTreeMap<i32, usize>,
+    /// Number of instructions that ended on each floor.
+    pub histogram: BTreeMap<i32, usize>,
+}
+
+impl FloorTrace {
+    pub fn first_reached(&self, floor: i32) -> Option<usize> {
+        self.first_visits.get(&floor).copied()
+    }
+
+    pub fn basement(&self) -> Option<usize> {
+        self.first_reached(-1)
+    }
+}
+
+/// Traces the instructions, skipping whitespace and rejecting anything but parentheses.
+pub fn trace(input: &str) -> Result<FloorTrace, ParseError> {
+    let mut trace = FloorTrace {
+        floor: 0,
+        lowest: (0, 0),
+        highest: (0, 0),
+        first_visits: BTreeMap::from([(0, 0)]),
+        histogram: BTreeMap::new(),
+    };
+    let (mut line, mut column) = (1, 0);
+    for (idx, c) in input.chars().enumerate() {
+        let position = idx + 1;
+        column += 1;
+        trace.floor += match c {
+            '(' => 1,
+            ')' => -1,
+            '\n' => {
+                line += 1;
+                column = 0;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            _ => return Err(ParseError::new(1, line, column, "`(` or `)`")),
+        };
+        let floor = trace.floor;
+        trace.first_visits.entry(floor).or_insert(position);
+        *trace.histogram.entry(floor).or_insert(0) += 1;
+        if floor < trace.lowest.0 {
+            trace.lowest = (floor, position);
+        }
+        if floor > trace.highest.0 {
+            trace.highest = (floor, position);
+        }
+    }
+    Ok(trace)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::day1::{solve_part1, solve_part2};
+    use crate::day1::{
+        basement_from_reader, floor_from_reader, solve_part1, solve_part1_swar, solve_part2,
+        solve_part2_blocks, trace,
+    };
+    use crate::parse::ParseError;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeMap;
+    use std::io::BufReader;
 
     /// For example:
     ///  - `(())` and `()()` both result in floor `0`.
@@ -61,4 +239,93 @@ mod test {
         assert_eq!(1, solve_part2(")"));
         assert_eq!(5, solve_part2("()())"));
     }
+
+    #[test]
+    fn streaming() {
+        for input in ["(())", "(()(()(", "))(((((", ")())())", "()())", ")"] {
+            let bytes = format!("{input}\n").into_bytes();
+            assert_eq!(
+                solve_part1(input),
+                floor_from_reader(BufReader::with_capacity(2, bytes.as_slice())).unwrap()
+            );
+        }
+        assert_eq!(Some(5), basement_from_reader("()())((".as_bytes()).unwrap());
+        assert_eq!(None, basement_from_reader("(()".as_bytes()).unwrap());
+        assert!(floor_from_reader("(x)".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn floor_trace() {
+        let trace = trace("()())((\n").unwrap();
+        assert_eq!(1, trace.floor);
+        assert_eq!((-1, 5), trace.lowest);
+        assert_eq!((1, 1), trace.highest);
+        assert_eq!(Some(5), trace.basement());
+        assert_eq!(Some(1), trace.first_reached(1));
+        assert_eq!(None, trace.first_reached(2));
+        assert_eq!(BTreeMap::from([(-1, 1), (0, 3), (1, 3)]), trace.histogram);
+    }
+
+    #[test]
+    fn floor_trace_counts_whitespace_positions() {
+        let trace = trace(" (\n)) (").unwrap();
+        assert_eq!(0, trace.floor);
+        assert_eq!((-1, 5), trace.lowest);
+        assert_eq!((1, 2), trace.highest);
+        assert_eq!(Some(2), trace.first_reached(1));
+        assert_eq!(Some(5), trace.basement());
+    }
+
+    #[test]
+    fn floor_trace_matches_solvers() {
+        for input in ["(())", "(()(()(", "))(((((", ")())())", "()())"] {
+            let trace = trace(input).unwrap();
+            assert_eq!(solve_part1(input), trace.floor);
+        }
+        assert_eq!(Some(solve_part2(")")), trace(")").unwrap().basement());
+    }
+
+    #[test]
+    fn floor_trace_errors() {
+        assert_eq!(
+            Some(ParseError::new(1, 2, 3, "`(` or `)`")),
+            trace("((\n()x)").err()
+        );
+    }
+
+    #[test]
+    fn chunked_matches_scalar() {
+        let mut rng = StdRng::seed_from_u64(2015);
+        for length in [1, 7, 8, 9, 255, 256, 257, 4096, 100_000] {
+            // Biased upwards so the basement is usually reached late, if at all.
+            let mut input: String = (0..length)
+                .map(|_| if rng.gen_bool(0.52) { '(' } else { ')' })
+                .collect();
+            assert_eq!(solve_part1(&input), solve_part1_swar(&input));
+            input.push_str(&")".repeat(length + 1));
+            assert_eq!(Some(solve_part2(&input)), solve_part2_blocks(&input));
+        }
+        for input in ["(())", "(()(()(", "))(((((", ")())())", "()())"] {
+            assert_eq!(solve_part1(input), solve_part1_swar(input));
+        }
+    }
+
+    #[test]
+    fn blocks_never_reaching_basement() {
+        assert_eq!(None, solve_part2_blocks(""));
+        assert_eq!(None, solve_part2_blocks("(()"));
+        assert_eq!(None, solve_part2_blocks(&"()".repeat(1000)));
+    }
+
+    #[test]
+    fn blocks_stop_at_basement_before_invalid_byte() {
+        let input = format!("{}){}x", "()".repeat(200), "(".repeat(300));
+        assert_eq!(Some(401), solve_part2_blocks(&input));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unexpected input")]
+    fn blocks_reject_invalid_byte() {
+        solve_part2_blocks(&format!("{}x))", "(".repeat(300)));
+    }
 }
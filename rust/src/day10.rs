@@ -2,14 +2,37 @@ use aoc_runner_derive::aoc;
 use itertools::Itertools;
 use std::iter::once;
 
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Params {
+    pub part1_repetitions: usize,
+    pub part2_repetitions: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_repetitions: 40,
+            part2_repetitions: 50,
+        }
+    }
+}
+
 #[aoc(day10, part1)]
 pub fn solve_part1(input: &str) -> usize {
-    tumble(input.trim(), 40).len()
+    solve_part1_with(&Params::default(), input)
 }
 
 #[aoc(day10, part2)]
 pub fn solve_part2(input: &str) -> usize {
-    tumble(input.trim(), 50).len()
+    solve_part2_with(&Params::default(), input)
+}
+
+pub fn solve_part1_with(params: &Params, input: &str) -> usize {
+    tumble(input.trim(), params.part1_repetitions).len()
+}
+
+pub fn solve_part2_with(params: &Params, input: &str) -> usize {
+    tumble(input.trim(), params.part2_repetitions).len()
 }
 fn tumble(input: &str, repetitions: usize) -> String {
     (0..repetitions).fold(input.to_string(), |acc, _| look_and_say(acc))
@@ -34,13 +57,23 @@ fn look_and_say(input: String) -> String {
 
 #[cfg(test)]
 mod test {
-    use crate::day10::{look_and_say, tumble};
+    use crate::day10::{look_and_say, solve_part1_with, solve_part2_with, tumble, Params};
 
     #[test]
     fn example() {
         assert_eq!("312211", tumble("1", 5));
     }
 
+    #[test]
+    fn custom_repetitions() {
+        let params = Params {
+            part1_repetitions: 5,
+            part2_repetitions: 6,
+        };
+        assert_eq!(6, solve_part1_with(&params, "1"));
+        assert_eq!(8, solve_part2_with(&params, "1"));
+    }
+
     #[test]
     fn look_and_say_examples() {
         let scenarios = [
@@ -112,12 +112,12 @@ mod test {
 
     #[test]
     fn test_trios() {
-        assert_eq!(true, increasing_trio(&"abc".chars().collect_vec()));
-        assert_eq!(true, increasing_trio(&"bcd".chars().collect_vec()));
-        assert_eq!(true, increasing_trio(&"cde".chars().collect_vec()));
-        assert_eq!(true, increasing_trio(&"xyz".chars().collect_vec()));
-        assert_eq!(false, increasing_trio(&"abd".chars().collect_vec()));
-        assert_eq!(true, increasing_trio(&"hijklmmn".chars().collect_vec()));
+        assert!(increasing_trio(&"abc".chars().collect_vec()));
+        assert!(increasing_trio(&"bcd".chars().collect_vec()));
+        assert!(increasing_trio(&"cde".chars().collect_vec()));
+        assert!(increasing_trio(&"xyz".chars().collect_vec()));
+        assert!(!increasing_trio(&"abd".chars().collect_vec()));
+        assert!(increasing_trio(&"hijklmmn".chars().collect_vec()));
     }
 
     #[test]
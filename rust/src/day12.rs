@@ -1,11 +1,18 @@
-use aoc_runner_derive::aoc;
+use crate::parse::ParseError;
+use aoc_runner_derive::{aoc, aoc_generator};
 use serde_json::Value;
 use std::collections::VecDeque;
 
+#[aoc_generator(day12)]
+pub fn try_parse_document(input: &str) -> Result<Value, ParseError> {
+    serde_json::from_str(input)
+        .map_err(|e| ParseError::new(12, e.line(), e.column(), "a JSON document"))
+}
+
 #[aoc(day12, part1)]
-pub fn solve_part1(input: &str) -> i64 {
+pub fn solve_part1(document: &Value) -> i64 {
     let mut sum = 0;
-    let mut queue: VecDeque<Value> = VecDeque::from([serde_json::from_str(input).unwrap()]);
+    let mut queue: VecDeque<Value> = VecDeque::from([document.clone()]);
 
     while let Some(value) = queue.pop_front() {
         match value {
@@ -26,9 +33,9 @@ pub fn solve_part1(input: &str) -> i64 {
 }
 
 #[aoc(day12, part2)]
-pub fn solve_part2(input: &str) -> i64 {
+pub fn solve_part2(document: &Value) -> i64 {
     let mut sum = 0;
-    let mut queue: VecDeque<Value> = VecDeque::from([serde_json::from_str(input).unwrap()]);
+    let mut queue: VecDeque<Value> = VecDeque::from([document.clone()]);
 
     while let Some(value) = queue.pop_front() {
         match value {
@@ -57,7 +64,16 @@ fn restricted(properties: &serde_json::Map<String, Value>) -> bool {
 
 #[cfg(test)]
 mod test {
-    use crate::day12::{solve_part1, solve_part2};
+    use crate::day12::try_parse_document;
+    use crate::parse::ParseError;
+
+    fn solve_part1(input: &str) -> i64 {
+        crate::day12::solve_part1(&try_parse_document(input).unwrap())
+    }
+
+    fn solve_part2(input: &str) -> i64 {
+        crate::day12::solve_part2(&try_parse_document(input).unwrap())
+    }
 
     #[test]
     fn part1_examples() {
@@ -78,4 +94,12 @@ mod test {
         assert_eq!(0, solve_part2("{\"d\":\"red\",\"e\":[1,2,3,4],\"f\":5}"));
         assert_eq!(6, solve_part2("[1,\"red\",5]"));
     }
+
+    #[test]
+    fn malformed_document() {
+        assert_eq!(
+            Some(ParseError::new(12, 2, 3, "a JSON document")),
+            try_parse_document("[1,\n2,]").err()
+        );
+    }
 }
@@ -1,17 +1,17 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, char, i32 as i32_nom, line_ending};
+use nom::character::complete::{alpha1, char, i32 as i32_nom};
 use nom::combinator::map;
-use nom::multi::separated_list1;
 use nom::sequence::{delimited, terminated, tuple};
 use nom::IResult;
 use petgraph::graphmap::DiGraphMap;
 
 #[aoc_generator(day13)]
-pub fn parse_happy_dances(input: &str) -> Vec<HappyDance> {
-    happy_dances(input).unwrap().1
+pub fn try_parse_happy_dances(input: &str) -> Result<Vec<HappyDance>, ParseError> {
+    parse::lines(13, input, happy_dance)
 }
 
 #[aoc(day13, part1)]
@@ -73,11 +73,8 @@ fn table_graph_with_self(dances: &[HappyDance]) -> DiGraphMap<&str, i32> {
     graph
 }
 
-type HappyDance = (String, String, i32);
+pub type HappyDance = (String, String, i32);
 
-fn happy_dances(input: &str) -> IResult<&str, Vec<HappyDance>> {
-    separated_list1(line_ending, happy_dance)(input)
-}
 fn happy_dance(input: &str) -> IResult<&str, HappyDance> {
     map(
         tuple((
@@ -105,7 +102,7 @@ fn happiness_loss(input: &str) -> IResult<&str, i32> {
 
 #[cfg(test)]
 mod test {
-    use crate::day13::{happy_dance, parse_happy_dances, solve_part1, solve_part2};
+    use crate::day13::{happy_dance, solve_part1, solve_part2, try_parse_happy_dances};
 
     const EXAMPLE: &str = r"Alice would gain 54 happiness units by sitting next to Bob.
 Alice would lose 79 happiness units by sitting next to Carol.
@@ -122,13 +119,13 @@ David would gain 41 happiness units by sitting next to Carol.
 ";
     #[test]
     fn example_part1() {
-        let dances = parse_happy_dances(EXAMPLE);
+        let dances = try_parse_happy_dances(EXAMPLE).unwrap();
         assert_eq!(330, solve_part1(&dances));
     }
 
     #[test]
     fn example_part2() {
-        let dances = parse_happy_dances(EXAMPLE);
+        let dances = try_parse_happy_dances(EXAMPLE).unwrap();
         assert_eq!(286, solve_part2(&dances));
     }
 
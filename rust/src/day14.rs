@@ -1,29 +1,48 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, line_ending, u32 as u32_nom};
+use nom::character::complete::{alpha1, u32 as u32_nom};
 use nom::combinator::map;
-use nom::multi::separated_list1;
 use nom::sequence::{delimited, terminated, tuple};
 use nom::IResult;
 use std::ops::{Div, Mul, Rem};
 
 #[aoc_generator(day14)]
-fn parse_olympic_herd(input: &str) -> Vec<Reindeer> {
-    herd(input).unwrap().1
+pub fn try_parse_olympic_herd(input: &str) -> Result<Vec<Reindeer>, ParseError> {
+    parse::lines(14, input, reindeer)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Params {
+    pub seconds: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { seconds: 2503 }
+    }
 }
 
 #[aoc(day14, part1)]
-fn solve_part1(herd: &[Reindeer]) -> u32 {
+pub fn solve_part1(herd: &[Reindeer]) -> u32 {
+    solve_part1_with(&Params::default(), herd)
+}
+
+#[aoc(day14, part2)]
+pub fn solve_part2(herd: &[Reindeer]) -> u32 {
+    solve_part2_with(&Params::default(), herd)
+}
+
+pub fn solve_part1_with(params: &Params, herd: &[Reindeer]) -> u32 {
     herd.iter()
-        .map(|reindeer| reindeer.distance_at(2503))
+        .map(|reindeer| reindeer.distance_at(params.seconds))
         .max()
         .unwrap()
 }
 
-#[aoc(day14, part2)]
-fn solve_part2(herd: &[Reindeer]) -> u32 {
-    (1..=2503)
+pub fn solve_part2_with(params: &Params, herd: &[Reindeer]) -> u32 {
+    (1..=params.seconds)
         .fold(
             herd.iter().map(|_| 0).collect_vec(),
             |scores: Vec<u32>, seconds| score(scores, seconds, herd),
@@ -45,9 +64,6 @@ fn score(scores: Vec<u32>, seconds: u32, herd: &[Reindeer]) -> Vec<u32> {
         .map(|(distance, score)| if distance == max { score + 1 } else { *score })
         .collect_vec()
 }
-fn herd(input: &str) -> IResult<&str, Vec<Reindeer>> {
-    separated_list1(line_ending, reindeer)(input)
-}
 
 fn reindeer(input: &str) -> IResult<&str, Reindeer> {
     map(
@@ -88,7 +104,7 @@ impl Reindeer {
 }
 #[cfg(test)]
 mod test {
-    use crate::day14::{reindeer, Reindeer};
+    use crate::day14::{reindeer, solve_part1_with, solve_part2_with, Params, Reindeer};
 
     #[test]
     fn parse_reindeer() {
@@ -117,6 +133,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn thousand_second_race() {
+        let herd = [
+            reindeer("Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.")
+                .unwrap()
+                .1,
+            reindeer("Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds.")
+                .unwrap()
+                .1,
+        ];
+        let params = Params { seconds: 1000 };
+        assert_eq!(1120, solve_part1_with(&params, &herd));
+        assert_eq!(689, solve_part2_with(&params, &herd));
+    }
+
     #[test]
     fn thosandth_second() {
         let comet = Reindeer {
@@ -1,17 +1,17 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, i32 as i32_nom, line_ending, u32 as u32_nom};
+use nom::character::complete::{alpha1, i32 as i32_nom, u32 as u32_nom};
 use nom::combinator::map;
-use nom::multi::separated_list1;
 use nom::sequence::{delimited, preceded, terminated, tuple};
 use nom::IResult;
 
 #[aoc_generator(day15)]
-fn parse_ingredients(input: &str) -> Vec<Ingredient> {
-    ingredients(input).unwrap().1
+pub fn try_parse_ingredients(input: &str) -> Result<Vec<Ingredient>, ParseError> {
+    parse::lines(15, input, ingredient)
 }
 #[aoc(day15, part1)]
-fn solve_part1(ingredients: &[Ingredient]) -> i32 {
+pub fn solve_part1(ingredients: &[Ingredient]) -> i32 {
     let mut best = 0;
 
     for i in 0..=100 {
@@ -23,7 +23,7 @@ fn solve_part1(ingredients: &[Ingredient]) -> i32 {
                 best = best.max(score(
                     ingredients
                         .iter()
-                        .zip([i, j, k, l].into_iter())
+                        .zip([i, j, k, l])
                         .map(|(ingredient, qty)| ingredient.scoop(qty))
                         .reduce(mix)
                         .unwrap(),
@@ -35,7 +35,7 @@ fn solve_part1(ingredients: &[Ingredient]) -> i32 {
 }
 
 #[aoc(day15, part2)]
-fn solve_part2(ingredients: &[Ingredient]) -> i32 {
+pub fn solve_part2(ingredients: &[Ingredient]) -> i32 {
     let mut best = 0;
 
     for i in 0..=100 {
@@ -46,7 +46,7 @@ fn solve_part2(ingredients: &[Ingredient]) -> i32 {
                 let l = rem2 - k;
                 let spoon = ingredients
                     .iter()
-                    .zip([i, j, k, l].into_iter())
+                    .zip([i, j, k, l])
                     .map(|(ingredient, qty)| ingredient.scoop(qty))
                     .reduce(mix)
                     .unwrap();
@@ -84,9 +84,6 @@ fn score((capacity, durability, flavor, texture, _): Spoonful) -> i32 {
         0
     }
 }
-fn ingredients(input: &str) -> IResult<&str, Vec<Ingredient>> {
-    separated_list1(line_ending, ingredient)(input)
-}
 
 fn ingredient(input: &str) -> IResult<&str, Ingredient> {
     map(
@@ -1,6 +1,7 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, line_ending, u32 as u32_nom, u8 as u8_nom};
+use nom::character::complete::{alpha1, u32 as u32_nom, u8 as u8_nom};
 use nom::combinator::map;
 use nom::multi::separated_list1;
 use nom::sequence::{preceded, terminated, tuple};
@@ -8,8 +9,8 @@ use nom::IResult;
 use std::collections::HashMap;
 
 #[aoc_generator(day16)]
-fn parse_aunts(input: &str) -> Vec<Sue> {
-    sues(input).unwrap().1
+pub fn try_parse_aunts(input: &str) -> Result<Vec<Sue>, ParseError> {
+    parse::lines(16, input, sue)
 }
 
 const CRITERIA: [(&str, u8); 10] = [
@@ -26,7 +27,7 @@ const CRITERIA: [(&str, u8); 10] = [
 ];
 
 #[aoc(day16, part1)]
-fn solve_part1(sues: &[Sue]) -> u32 {
+pub fn solve_part1(sues: &[Sue]) -> u32 {
     sues.iter()
         .find(|sue| {
             CRITERIA
@@ -38,7 +39,7 @@ fn solve_part1(sues: &[Sue]) -> u32 {
 }
 
 #[aoc(day16, part2)]
-fn solve_part2(sues: &[Sue]) -> u32 {
+pub fn solve_part2(sues: &[Sue]) -> u32 {
     sues.iter()
         .find(|sue| {
             CRITERIA
@@ -49,10 +50,6 @@ fn solve_part2(sues: &[Sue]) -> u32 {
         .number
 }
 
-fn sues(input: &str) -> IResult<&str, Vec<Sue>> {
-    separated_list1(line_ending, sue)(input)
-}
-
 fn sue(input: &str) -> IResult<&str, Sue> {
     map(
         tuple((
@@ -79,7 +76,7 @@ fn attribute_name(input: &str) -> IResult<&str, String> {
 }
 
 #[derive(Debug)]
-struct Sue {
+pub struct Sue {
     number: u32,
     attributes: HashMap<String, u8>,
 }
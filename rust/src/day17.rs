@@ -1,24 +1,41 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use nom::character::complete::u32 as u32_nom;
 use std::ops::Range;
-use std::str::FromStr;
 
 #[aoc_generator(day17)]
-fn parse_containers(input: &str) -> Vec<u32> {
-    input
-        .lines()
-        .map(|line| u32::from_str(line).unwrap())
-        .collect_vec()
+pub fn try_parse_containers(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::lines(17, input, u32_nom)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Params {
+    pub liters: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { liters: 150 }
+    }
 }
 
 #[aoc(day17, part1)]
-fn solve_part1(containers: &[u32]) -> usize {
-    combo_count(150, containers)
+pub fn solve_part1(containers: &[u32]) -> usize {
+    solve_part1_with(&Params::default(), containers)
 }
 
 #[aoc(day17, part2)]
-fn solve_part2(containers: &[u32]) -> usize {
-    minimim_combos(150, containers)
+pub fn solve_part2(containers: &[u32]) -> usize {
+    solve_part2_with(&Params::default(), containers)
+}
+
+pub fn solve_part1_with(params: &Params, containers: &[u32]) -> usize {
+    combo_count(params.liters, containers)
+}
+
+pub fn solve_part2_with(params: &Params, containers: &[u32]) -> usize {
+    minimim_combos(params.liters, containers)
 }
 
 fn minimim_combos(liters: u32, containers: &[u32]) -> usize {
@@ -88,7 +105,7 @@ fn min_combo(liters: u32, containers: &[u32]) -> u32 {
 
 #[cfg(test)]
 mod test {
-    use crate::day17::{combo_count, minimim_combos};
+    use crate::day17::{combo_count, minimim_combos, solve_part1_with, solve_part2_with, Params};
 
     #[test]
     fn example_count() {
@@ -101,4 +118,12 @@ mod test {
         let containers = [20, 15, 10, 5, 5];
         assert_eq!(3, minimim_combos(25, &containers));
     }
+
+    #[test]
+    fn example_params() {
+        let containers = [20, 15, 10, 5, 5];
+        let params = Params { liters: 25 };
+        assert_eq!(4, solve_part1_with(&params, &containers));
+        assert_eq!(3, solve_part2_with(&params, &containers));
+    }
 }
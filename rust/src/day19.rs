@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::bytes::complete::tag;
@@ -9,12 +10,12 @@ use nom::IResult;
 use rand::seq::SliceRandom;
 
 #[aoc_generator(day19)]
-fn parse_rudolph_meds(input: &str) -> RudolphMeds {
-    rudolph_meds(input).unwrap().1
+pub fn try_parse_rudolph_meds(input: &str) -> Result<RudolphMeds, ParseError> {
+    parse::complete(19, input, rudolph_meds)
 }
 
 #[aoc(day19, part1)]
-fn solve_part1(input: &RudolphMeds) -> usize {
+pub fn solve_part1(input: &RudolphMeds) -> usize {
     input
         .replacements
         .iter()
@@ -112,7 +113,7 @@ impl Replacement {
 
 #[cfg(test)]
 mod test {
-    use crate::day19::{parse_rudolph_meds, rudolph_meds, solve_part1};
+    use crate::day19::{rudolph_meds, solve_part1, try_parse_rudolph_meds};
 
     const EXAMPLE: &str = r"H => HO
 H => OH
@@ -129,7 +130,7 @@ HOH";
 
     #[test]
     fn example() {
-        let rudolph_meds = parse_rudolph_meds(EXAMPLE.trim());
+        let rudolph_meds = try_parse_rudolph_meds(EXAMPLE.trim()).unwrap();
         assert_eq!(4, solve_part1(&rudolph_meds));
     }
 }
@@ -1,31 +1,146 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::character::complete::{char, u32 as nom_u32};
-use nom::combinator::map;
-use nom::sequence::{delimited, tuple};
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, space0, space1};
+use nom::combinator::{consumed, map, opt, recognize, value};
+use nom::sequence::{pair, tuple};
 use nom::IResult;
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 
+/// Gifts measured in whole feet, as the elves do.
+///
+/// Only whole units are accepted: every dimension is converted to feet and has to come out a whole
+/// number, so `2.5x3x4` is rejected. To accept fractional feet, parse with [try_parse_gifts_in] in
+/// a finer unit and write the dimensions with an `ft` suffix, as in `2.5ftx3ftx4ft`.
 #[aoc_generator(day2)]
-fn parse_gifts(input: &str) -> Vec<Gift> {
-    input.lines().map(|line| gift(line).unwrap().1).collect()
+pub fn try_parse_gifts(input: &str) -> Result<Vec<Gift>, ParseError> {
+    try_parse_gifts_in(input, Unit::Foot)
+}
+
+/// Parses `LxWxH`, `L*W*H` or `L W H` lines, converting every dimension to `unit`.
+///
+/// Dimensions may be decimal and carry a `cm`, `in` or `ft` suffix, those without one are taken
+/// to be in `unit` already. Each converted dimension has to be a whole number, and the paper and
+/// ribbon for the gift have to fit in a `u32`. Centimeters are the finest unit, so a dimension
+/// like `2.5in` (6.35cm) is rejected whatever `unit` is.
+pub fn try_parse_gifts_in(input: &str, unit: Unit) -> Result<Vec<Gift>, ParseError> {
+    parse::lines_checked(2, input, gift, |[l, w, h]: [Measure; 3]| {
+        Gift::new(l.to(unit)?, w.to(unit)?, h.to(unit)?).ok_or_else(|| {
+            (
+                l.token,
+                "a gift small enough for its paper and ribbon to fit in 32 bits".to_string(),
+            )
+        })
+    })
 }
 
 #[aoc(day2, part1)]
-pub fn solve_part1(gifts: &[Gift]) -> u32 {
-    gifts.iter().map(|gift| gift.wrapping_paper()).sum()
+pub fn solve_part1(gifts: &[Gift]) -> u64 {
+    gifts.iter().map(|gift| gift.wrapping_paper() as u64).sum()
 }
 #[aoc(day2, part2)]
-pub fn solve_part2(gifts: &[Gift]) -> u32 {
-    gifts.iter().map(|gift| gift.ribbon()).sum()
+pub fn solve_part2(gifts: &[Gift]) -> u64 {
+    gifts.iter().map(|gift| gift.ribbon() as u64).sum()
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Unit {
+    Centimeter,
+    Inch,
+    Foot,
+}
+
+impl Unit {
+    /// Length in tenths of a millimeter, the largest unit all others are whole multiples of.
+    fn tenths_of_millimeter(&self) -> u128 {
+        match self {
+            Unit::Centimeter => 100,
+            Unit::Inch => 254,
+            Unit::Foot => 3048,
+        }
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let suffix = match self {
+            Unit::Centimeter => "cm",
+            Unit::Inch => "in",
+            Unit::Foot => "ft",
+        };
+        write!(f, "{suffix}")
+    }
+}
+
+/// A dimension as written, before conversion.
+#[derive(Debug, Clone, Copy)]
+struct Measure<'a> {
+    token: &'a str,
+    number: &'a str,
+    unit: Option<Unit>,
+}
+
+impl<'a> Measure<'a> {
+    /// Exact conversion to a whole number of `unit`.
+    fn to(self, unit: Unit) -> Result<u32, (&'a str, String)> {
+        let too_large = || (self.token, "a dimension that fits in 32 bits".to_string());
+        let (whole, fraction) = self.number.split_once('.').unwrap_or((self.number, ""));
+        let mantissa: u128 = format!("{whole}{fraction}")
+            .parse()
+            .map_err(|_| too_large())?;
+        let scale = 10u128
+            .checked_pow(fraction.len() as u32)
+            .ok_or_else(too_large)?;
+        let from = self.unit.unwrap_or(unit);
+        let numerator = mantissa
+            .checked_mul(from.tenths_of_millimeter())
+            .ok_or_else(too_large)?;
+        let denominator = scale
+            .checked_mul(unit.tenths_of_millimeter())
+            .ok_or_else(too_large)?;
+        if numerator % denominator != 0 {
+            return Err((self.token, format!("a whole number of {unit}")));
+        }
+        u32::try_from(numerator / denominator).map_err(|_| too_large())
+    }
 }
 
-fn gift(input: &str) -> IResult<&str, Gift> {
+fn gift(input: &str) -> IResult<&str, [Measure<'_>; 3]> {
     map(
-        tuple((nom_u32, delimited(char('x'), nom_u32, char('x')), nom_u32)),
-        |(l, w, h)| Gift { l, w, h },
+        tuple((measure, separator, measure, separator, measure)),
+        |(l, _, w, _, h)| [l, w, h],
     )(input)
 }
 
-#[derive(Debug)]
+fn measure(input: &str) -> IResult<&str, Measure<'_>> {
+    map(
+        consumed(pair(
+            recognize(pair(digit1, opt(pair(char('.'), digit1)))),
+            opt(alt((
+                value(Unit::Centimeter, tag("cm")),
+                value(Unit::Inch, tag("in")),
+                value(Unit::Foot, tag("ft")),
+            ))),
+        )),
+        |(token, (number, unit))| Measure {
+            token,
+            number,
+            unit,
+        },
+    )(input)
+}
+
+/// `x` or `*`, optionally surrounded by spaces, or just spaces.
+fn separator(input: &str) -> IResult<&str, ()> {
+    let symbol = || pair(alt((char('x'), char('*'))), space0);
+    alt((value((), pair(space1, opt(symbol()))), value((), symbol())))(input)
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Clone, Copy)]
 pub struct Gift {
     l: u32,
     w: u32,
@@ -33,6 +148,26 @@ pub struct Gift {
 }
 
 impl Gift {
+    /// `None` when the paper or ribbon for the gift would overflow a `u32`.
+    pub fn new(l: u32, w: u32, h: u32) -> Option<Self> {
+        let [l_, w_, h_] = [l as u128, w as u128, h as u128];
+        let surfaces = [l_ * w_, w_ * h_, h_ * l_];
+        let paper = surfaces.iter().sum::<u128>() * 2 + surfaces.iter().min().unwrap();
+        let ribbon = (l_ + w_ + h_ - l_.max(w_).max(h_)) * 2 + l_ * w_ * h_;
+        (paper <= u32::MAX as u128 && ribbon <= u32::MAX as u128).then_some(Gift { l, w, h })
+    }
+
+    pub fn dimensions(&self) -> [u32; 3] {
+        [self.l, self.w, self.h]
+    }
+
+    /// The two shortest sides, spanning the smallest face.
+    fn smallest_face(&self) -> [u32; 2] {
+        let mut sides = self.dimensions();
+        sides.sort();
+        [sides[0], sides[1]]
+    }
+
     fn surfaces(&self) -> [u32; 3] {
         [self.l * self.w, self.w * self.h, self.h * self.l]
     }
@@ -57,11 +192,178 @@ impl Gift {
     fn ribbon(&self) -> u32 {
         self.ribbon_wrap() + self.ribbon_bow()
     }
+
+    pub fn breakdown(&self) -> Breakdown {
+        let smallest_face = self.smallest_face();
+        Breakdown {
+            gift: *self,
+            smallest_face,
+            slack: smallest_face[0] * smallest_face[1],
+            paper: self.wrapping_paper(),
+            wrap: self.ribbon_wrap(),
+            bow: self.ribbon_bow(),
+            ribbon: self.ribbon(),
+        }
+    }
+}
+
+/// Material needed for a single gift.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Breakdown {
+    pub gift: Gift,
+    pub smallest_face: [u32; 2],
+    /// Extra paper, the area of the smallest face.
+    pub slack: u32,
+    pub paper: u32,
+    /// Ribbon around the smallest perimeter.
+    pub wrap: u32,
+    /// Ribbon for the bow, the volume of the gift.
+    pub bow: u32,
+    pub ribbon: u32,
+}
+
+/// Gifts with identical dimensions and the material they need together.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Group {
+    pub gift: Gift,
+    pub count: usize,
+    pub paper: u64,
+    pub ribbon: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Stats {
+    pub gifts: usize,
+    pub paper: u64,
+    pub ribbon: u64,
+    pub mean_paper: f64,
+    pub mean_ribbon: f64,
+    /// Gift needing the most paper, the first one on ties.
+    pub most_paper: Option<Breakdown>,
+    /// Gift needing the most ribbon, the first one on ties.
+    pub most_ribbon: Option<Breakdown>,
+}
+
+/// Order of gifts to audit the material usage of.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Manifest {
+    gifts: Vec<Gift>,
+}
+
+impl Manifest {
+    pub fn new(gifts: Vec<Gift>) -> Self {
+        Manifest { gifts }
+    }
+
+    pub fn gifts(&self) -> &[Gift] {
+        &self.gifts
+    }
+
+    pub fn breakdown(&self) -> Vec<Breakdown> {
+        self.gifts.iter().map(Gift::breakdown).collect()
+    }
+
+    pub fn stats(&self) -> Stats {
+        let breakdown = self.breakdown();
+        let paper: u64 = breakdown.iter().map(|b| b.paper as u64).sum();
+        let ribbon: u64 = breakdown.iter().map(|b| b.ribbon as u64).sum();
+        let count = breakdown.len().max(1) as f64;
+        Stats {
+            gifts: breakdown.len(),
+            paper,
+            ribbon,
+            mean_paper: paper as f64 / count,
+            mean_ribbon: ribbon as f64 / count,
+            most_paper: first_max_by_key(&breakdown, |b| b.paper),
+            most_ribbon: first_max_by_key(&breakdown, |b| b.ribbon),
+        }
+    }
+
+    /// Identical gifts grouped together, the groups using the most paper first.
+    pub fn groups(&self) -> Vec<Group> {
+        let mut index: HashMap<Gift, usize> = HashMap::new();
+        let mut groups: Vec<Group> = vec![];
+        for gift in &self.gifts {
+            let idx = *index.entry(*gift).or_insert_with(|| {
+                groups.push(Group {
+                    gift: *gift,
+                    count: 0,
+                    paper: 0,
+                    ribbon: 0,
+                });
+                groups.len() - 1
+            });
+            let group = &mut groups[idx];
+            group.count += 1;
+            group.paper += gift.wrapping_paper() as u64;
+            group.ribbon += gift.ribbon() as u64;
+        }
+        groups.sort_by_key(|group| Reverse(group.paper));
+        groups
+    }
+
+    /// One row per gift with a header line.
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec!["l,w,h,smallest_face,slack,paper,wrap,bow,ribbon".to_string()];
+        lines.extend(self.breakdown().iter().map(|b| {
+            let [l, w, h] = b.gift.dimensions();
+            format!(
+                "{l},{w},{h},{}x{},{},{},{},{},{}",
+                b.smallest_face[0], b.smallest_face[1], b.slack, b.paper, b.wrap, b.bow, b.ribbon
+            )
+        }));
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> Value {
+        let stats = self.stats();
+        json!({
+            "gifts": self.breakdown().iter().map(breakdown_json).collect::<Vec<_>>(),
+            "groups": self.groups().iter().map(|group| json!({
+                "dimensions": group.gift.dimensions(),
+                "count": group.count,
+                "paper": group.paper,
+                "ribbon": group.ribbon,
+            })).collect::<Vec<_>>(),
+            "stats": {
+                "gifts": stats.gifts,
+                "paper": stats.paper,
+                "ribbon": stats.ribbon,
+                "mean_paper": stats.mean_paper,
+                "mean_ribbon": stats.mean_ribbon,
+                "most_paper": stats.most_paper.as_ref().map(breakdown_json),
+                "most_ribbon": stats.most_ribbon.as_ref().map(breakdown_json),
+            },
+        })
+    }
+}
+
+fn first_max_by_key(breakdown: &[Breakdown], key: impl Fn(&Breakdown) -> u32) -> Option<Breakdown> {
+    breakdown
+        .iter()
+        .copied()
+        .reduce(|best, b| if key(&b) > key(&best) { b } else { best })
+}
+
+fn breakdown_json(b: &Breakdown) -> Value {
+    json!({
+        "dimensions": b.gift.dimensions(),
+        "smallest_face": b.smallest_face,
+        "slack": b.slack,
+        "paper": b.paper,
+        "wrap": b.wrap,
+        "bow": b.bow,
+        "ribbon": b.ribbon,
+    })
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day2::Gift;
+    use crate::day2::{
+        solve_part1, solve_part2, try_parse_gifts, try_parse_gifts_in, Breakdown, Gift, Group,
+        Manifest, Unit,
+    };
+    use crate::parse::ParseError;
 
     #[test]
     fn ribbon() {
@@ -75,4 +377,154 @@ mod test {
         assert_eq!(10, gift2.ribbon_bow());
         assert_eq!(14, gift2.ribbon());
     }
+
+    #[test]
+    fn malformed_gift() {
+        assert_eq!(
+            Some(ParseError::new(2, 2, 4, "punctuation")),
+            try_parse_gifts("2x3x4\n1x1-10").err()
+        );
+    }
+
+    #[test]
+    fn dimension_formats() {
+        let expected = vec![Gift::new(2, 3, 4).unwrap(); 4];
+        assert_eq!(
+            Ok(expected),
+            try_parse_gifts("2x3x4\n2*3*4\n2 3 4\n2 x 3  *4")
+        );
+    }
+
+    #[test]
+    fn surrounding_whitespace() {
+        assert_eq!(
+            Ok(vec![
+                Gift::new(2, 3, 4).unwrap(),
+                Gift::new(1, 1, 10).unwrap()
+            ]),
+            try_parse_gifts("2x3x4 \n\t1x1x10\r\n")
+        );
+        assert_eq!(
+            Some(ParseError::new(2, 1, 4, "punctuation")),
+            try_parse_gifts("  2!3x4").err()
+        );
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(
+            Ok(vec![Gift::new(18, 12, 2).unwrap()]),
+            try_parse_gifts_in("1.5ft x 1ft x 5.08cm", Unit::Inch)
+        );
+        assert_eq!(
+            Ok(vec![Gift::new(254, 1000, 3).unwrap()]),
+            try_parse_gifts_in("100in 1000.0 3cm", Unit::Centimeter)
+        );
+        assert_eq!(
+            Some(ParseError::new(2, 1, 3, "a whole number of ft")),
+            try_parse_gifts("1x1inx1").err()
+        );
+    }
+
+    #[test]
+    fn decimal_dimensions() {
+        assert_eq!(
+            Some(ParseError::new(2, 1, 1, "a whole number of ft")),
+            try_parse_gifts("2.5x3x4").err()
+        );
+        assert_eq!(
+            Ok(vec![Gift::new(30, 36, 48).unwrap()]),
+            try_parse_gifts_in("2.5ftx3ftx4ft", Unit::Inch)
+        );
+        assert_eq!(
+            Some(ParseError::new(2, 1, 1, "a whole number of cm")),
+            try_parse_gifts_in("2.5in x 6 x 8", Unit::Centimeter).err()
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(
+            Some(ParseError::new(2, 2, 1, "a dimension that fits in 32 bits")),
+            try_parse_gifts("1x1x1\n4294967296x1x1").err()
+        );
+        assert_eq!(
+            Some(ParseError::new(
+                2,
+                1,
+                1,
+                "a gift small enough for its paper and ribbon to fit in 32 bits"
+            )),
+            try_parse_gifts("2000x2000x2000").err()
+        );
+        assert_eq!(None, Gift::new(u32::MAX, 1, 1));
+    }
+
+    #[test]
+    fn large_totals() {
+        let gifts = try_parse_gifts(&"1000x1000x1000\n".repeat(3000)).unwrap();
+        assert_eq!(3000 * 7_000_000, solve_part1(&gifts));
+        assert_eq!(3000 * 1_000_004_000, solve_part2(&gifts));
+    }
+
+    #[test]
+    fn manifest() {
+        let manifest = Manifest::new(try_parse_gifts("2x3x4\n1x1x10\n4x3x2\n2x3x4").unwrap());
+        let first = Breakdown {
+            gift: Gift::new(2, 3, 4).unwrap(),
+            smallest_face: [2, 3],
+            slack: 6,
+            paper: 58,
+            wrap: 10,
+            bow: 24,
+            ribbon: 34,
+        };
+        assert_eq!(first, manifest.breakdown()[0]);
+
+        let stats = manifest.stats();
+        assert_eq!(4, stats.gifts);
+        assert_eq!(58 * 3 + 43, stats.paper);
+        assert_eq!(34 * 3 + 14, stats.ribbon);
+        assert_eq!(Some(first), stats.most_paper);
+
+        assert_eq!(
+            vec![
+                Group {
+                    gift: Gift::new(2, 3, 4).unwrap(),
+                    count: 2,
+                    paper: 116,
+                    ribbon: 68
+                },
+                Group {
+                    gift: Gift::new(4, 3, 2).unwrap(),
+                    count: 1,
+                    paper: 58,
+                    ribbon: 34
+                },
+                Group {
+                    gift: Gift::new(1, 1, 10).unwrap(),
+                    count: 1,
+                    paper: 43,
+                    ribbon: 14
+                },
+            ],
+            manifest.groups()
+        );
+    }
+
+    #[test]
+    fn manifest_export() {
+        let manifest = Manifest::new(try_parse_gifts("2x3x4\n1x1x10").unwrap());
+        assert_eq!(
+            "l,w,h,smallest_face,slack,paper,wrap,bow,ribbon\n2,3,4,2x3,6,58,10,24,34\n1,1,10,1x1,1,43,4,10,14",
+            manifest.to_csv()
+        );
+        let json = manifest.to_json();
+        assert_eq!(101, json["stats"]["paper"]);
+        assert_eq!(2, json["gifts"].as_array().unwrap().len());
+        assert_eq!(
+            serde_json::json!([1, 1, 10]),
+            json["groups"][1]["dimensions"]
+        );
+    }
 }
@@ -1,38 +1,113 @@
-use aoc_runner_derive::aoc;
+use crate::parse::{self, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+use nom::character::complete::u64 as u64_nom;
+use nom::combinator::map_res;
 use std::ops::Div;
-use std::str::FromStr;
-use std::usize;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Params {
+    /// Number of houses simulated, the answer must be below this.
+    pub houses: usize,
+    pub part1_presents: usize,
+    pub part2_presents: usize,
+    /// Houses each elf visits in part 2.
+    pub part2_visits: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            houses: 1_000_000,
+            part1_presents: 10,
+            part2_presents: 11,
+            part2_visits: 50,
+        }
+    }
+}
+
+/// Number of presents the first house has to get.
+#[aoc_generator(day20)]
+pub fn try_parse_presents(input: &str) -> Result<usize, ParseError> {
+    parse::complete(20, input, map_res(u64_nom, usize::try_from))
+}
 
 #[aoc(day20, part1)]
-fn solve_part1(input: &str) -> usize {
-    let min = usize::from_str(input).unwrap().div(10);
-    let mut houses = [0; 1_000_000];
-    let max = houses.len() - 1;
-    (1..)
-        .find(|&elf| {
-            (elf..=max)
-                .step_by(elf)
-                .filter(|&h| h < max)
-                .for_each(|house| houses[house] += elf);
-            houses[elf] >= min
-        })
-        .unwrap()
+pub fn solve_part1(presents: &usize) -> Option<usize> {
+    solve_part1_with(&Params::default(), *presents)
 }
 
 #[aoc(day20, part2)]
-fn solve_part2(input: &str) -> usize {
-    let min = usize::from_str(input).unwrap().div(11);
-    let mut houses = [0; 1_000_000];
-    let max = houses.len() - 1;
-    (1..)
-        .find(|&elf| {
-            (1..=50)
-                .filter_map(|step| match step * elf {
-                    house if house < max => Some(house),
-                    _ => None,
-                })
-                .for_each(|house| houses[house] += elf);
-            houses[elf] >= min
-        })
-        .unwrap()
+pub fn solve_part2(presents: &usize) -> Option<usize> {
+    solve_part2_with(&Params::default(), *presents)
+}
+
+/// Lowest house getting at least `presents`, `None` when it is not among the simulated houses.
+pub fn solve_part1_with(params: &Params, presents: usize) -> Option<usize> {
+    let min = presents.div(params.part1_presents);
+    let mut houses = vec![0; params.houses];
+    let max = houses.len().saturating_sub(1);
+    (1..params.houses).find(|&elf| {
+        (elf..=max)
+            .step_by(elf)
+            .filter(|&h| h < max)
+            .for_each(|house| houses[house] += elf);
+        houses[elf] >= min
+    })
+}
+
+pub fn solve_part2_with(params: &Params, presents: usize) -> Option<usize> {
+    let min = presents.div(params.part2_presents);
+    let mut houses = vec![0; params.houses];
+    let max = houses.len().saturating_sub(1);
+    (1..params.houses).find(|&elf| {
+        (1..=params.part2_visits)
+            .filter_map(|step| match step * elf {
+                house if house < max => Some(house),
+                _ => None,
+            })
+            .for_each(|house| houses[house] += elf);
+        houses[elf] >= min
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::day20::{solve_part1_with, solve_part2_with, try_parse_presents, Params};
+    use crate::parse::ParseError;
+
+    #[test]
+    fn small_street() {
+        let params = Params {
+            houses: 100,
+            ..Params::default()
+        };
+        assert_eq!(Some(4), solve_part1_with(&params, 70));
+        assert_eq!(Some(8), solve_part1_with(&params, 150));
+        assert_eq!(Some(6), solve_part2_with(&params, 132));
+    }
+
+    #[test]
+    fn beyond_the_street() {
+        let params = Params {
+            houses: 10,
+            ..Params::default()
+        };
+        assert_eq!(Some(8), solve_part1_with(&params, 150));
+        assert_eq!(None, solve_part1_with(&params, 1_000));
+        assert_eq!(None, solve_part2_with(&params, 1_000));
+        let empty = Params {
+            houses: 0,
+            ..Params::default()
+        };
+        assert_eq!(None, solve_part1_with(&empty, 70));
+    }
+
+    #[test]
+    fn presents() {
+        assert_eq!(Ok(29_000_000), try_parse_presents("29000000\n"));
+        assert_eq!(
+            Some(ParseError::new(20, 1, 1, "number")),
+            try_parse_presents("lots").err()
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::{iproduct, Itertools};
 use nom::bytes::complete::tag;
@@ -9,8 +10,8 @@ use std::iter::Sum;
 use std::ops::Add;
 
 #[aoc_generator(day21)]
-fn parse_boss(input: &str) -> Role {
-    boss(input).unwrap().1
+pub fn try_parse_boss(input: &str) -> Result<Role, ParseError> {
+    parse::complete(21, input, boss)
 }
 
 fn boss(input: &str) -> IResult<&str, Role> {
@@ -24,22 +25,41 @@ fn boss(input: &str) -> IResult<&str, Role> {
     )(input)
 }
 
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Params {
+    pub player_hp: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { player_hp: 100 }
+    }
+}
+
 #[aoc(day21, part1)]
-fn solve_part1(boss: &Role) -> u32 {
+pub fn solve_part1(boss: &Role) -> u32 {
+    solve_part1_with(&Params::default(), boss)
+}
+
+#[aoc(day21, part2)]
+pub fn solve_part2(boss: &Role) -> u32 {
+    solve_part2_with(&Params::default(), boss)
+}
+
+pub fn solve_part1_with(params: &Params, boss: &Role) -> u32 {
     item_combinations()
         .into_iter()
-        .map(|item| (item.cost, Role::from_item(item.clone())))
+        .map(|item| (item.cost, Role::from_item(item.clone(), params.player_hp)))
         .filter(|(_, role)| battle(role.clone(), boss.clone()))
         .map(|(cost, _)| cost)
         .min()
         .unwrap_or(0)
 }
 
-#[aoc(day21, part2)]
-fn solve_part2(boss: &Role) -> u32 {
+pub fn solve_part2_with(params: &Params, boss: &Role) -> u32 {
     item_combinations()
         .into_iter()
-        .map(|item| (item.cost, Role::from_item(item.clone())))
+        .map(|item| (item.cost, Role::from_item(item.clone(), params.player_hp)))
         .filter(|(_, role)| !battle(role.clone(), boss.clone()))
         .map(|(cost, _)| cost)
         .max()
@@ -161,16 +181,16 @@ impl Sum for ShopItem {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct Role {
+pub struct Role {
     hp: u32,
     damage: u32,
     armor: u32,
 }
 
 impl Role {
-    fn from_item(item: ShopItem) -> Self {
+    fn from_item(item: ShopItem, hp: u32) -> Self {
         Role {
-            hp: 100,
+            hp,
             damage: item.damage,
             armor: item.armor,
         }
@@ -197,7 +217,7 @@ mod test {
             damage: 7,
             armor: 2,
         };
-        assert_eq!(true, battle(player, boss));
+        assert!(battle(player, boss));
     }
 
     #[test]
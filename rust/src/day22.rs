@@ -1,4 +1,5 @@
 use crate::day22::Spell::{Drain, MagicMissile, Poison, Recharge, Shield};
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::bytes::complete::tag;
@@ -10,8 +11,8 @@ use std::collections::VecDeque;
 use std::ops::Add;
 
 #[aoc_generator(day22)]
-fn parse_boss(input: &str) -> Boss {
-    boss(input).unwrap().1
+pub fn try_parse_boss(input: &str) -> Result<Boss, ParseError> {
+    parse::complete(22, input, boss)
 }
 
 fn boss(input: &str) -> IResult<&str, Boss> {
@@ -24,22 +25,46 @@ fn boss(input: &str) -> IResult<&str, Boss> {
     )(input)
 }
 
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Params {
+    pub player_hp: u32,
+    pub player_mana: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            player_hp: 50,
+            player_mana: 500,
+        }
+    }
+}
+
 #[aoc(day22, part1)]
-fn solve_part1(boss: &Boss) -> u32 {
-    let player = Player { hp: 50, mana: 500 };
+pub fn solve_part1(boss: &Boss) -> Option<u32> {
+    solve_part1_with(&Params::default(), boss)
+}
+
+#[aoc(day22, part2)]
+pub fn solve_part2(boss: &Boss) -> Option<u32> {
+    solve_part2_with(&Params::default(), boss)
+}
+
+/// Least mana to spend and still win, `None` when the boss cannot be beaten.
+pub fn solve_part1_with(params: &Params, boss: &Boss) -> Option<u32> {
+    let player = Player::from(params);
     let start = GameState::new(player, boss, false);
     play(start)
 }
 
-#[aoc(day22, part2)]
-fn solve_part2(boss: &Boss) -> u32 {
-    let player = Player { hp: 50, mana: 500 };
+pub fn solve_part2_with(params: &Params, boss: &Boss) -> Option<u32> {
+    let player = Player::from(params);
     let start = GameState::new(player, boss, true);
     play(start)
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct Boss {
+pub struct Boss {
     hp: u32,
     damage: u32,
 }
@@ -49,6 +74,15 @@ struct Player {
     mana: u32,
 }
 
+impl From<&Params> for Player {
+    fn from(params: &Params) -> Self {
+        Player {
+            hp: params.player_hp,
+            mana: params.player_mana,
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum Spell {
     MagicMissile,
@@ -163,20 +197,19 @@ impl GameState {
     }
 }
 
-fn play(start: GameState) -> u32 {
+fn play(start: GameState) -> Option<u32> {
     let mut queue = VecDeque::from([start]);
-    let mut min = u32::MAX;
+    let mut min = None;
     while let Some(state) = queue.pop_front() {
-        if state.mana_spent <= min {
-            if state.boss_hp == 0 {
-                if min > state.mana_spent {
-                    min = state.mana_spent;
-                }
-            } else if state.hp > 0 {
-                state.castable_spells().into_iter().for_each(|spell| {
-                    queue.push_back(state.play_round(spell));
-                })
-            }
+        if min.is_some_and(|min| state.mana_spent > min) {
+            continue;
+        }
+        if state.boss_hp == 0 {
+            min = Some(state.mana_spent);
+        } else if state.hp > 0 {
+            state.castable_spells().into_iter().for_each(|spell| {
+                queue.push_back(state.play_round(spell));
+            })
         }
     }
     min
@@ -185,7 +218,7 @@ fn play(start: GameState) -> u32 {
 #[cfg(test)]
 mod test {
     use crate::day22::Spell::{Drain, MagicMissile, Poison, Recharge, Shield};
-    use crate::day22::{Boss, GameState, Player};
+    use crate::day22::{solve_part1_with, solve_part2_with, Boss, GameState, Params, Player};
 
     #[test]
     fn first_example() {
@@ -217,4 +250,35 @@ mod test {
         assert_eq!(114, game.mana);
         assert_eq!(0, game.shield_timer);
     }
+
+    #[test]
+    fn example_params() {
+        let params = Params {
+            player_hp: 10,
+            player_mana: 250,
+        };
+        assert_eq!(
+            Some(173 + 53),
+            solve_part1_with(&params, &Boss { hp: 13, damage: 8 })
+        );
+        assert_eq!(
+            Some(229 + 113 + 73 + 173 + 53),
+            solve_part1_with(&params, &Boss { hp: 14, damage: 8 })
+        );
+    }
+
+    #[test]
+    fn unbeatable_boss() {
+        let params = Params {
+            player_hp: 10,
+            player_mana: 100,
+        };
+        assert_eq!(None, solve_part1_with(&params, &Boss { hp: 13, damage: 8 }));
+        let frail = Params {
+            player_hp: 1,
+            ..Params::default()
+        };
+        assert!(solve_part1_with(&frail, &Boss { hp: 1, damage: 1 }).is_some());
+        assert_eq!(None, solve_part2_with(&frail, &Boss { hp: 1, damage: 1 }));
+    }
 }
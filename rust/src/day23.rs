@@ -1,24 +1,24 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{anychar, i32 as i32_nom, line_ending};
+use nom::character::complete::{anychar, i32 as i32_nom};
 use nom::combinator::map;
-use nom::multi::separated_list1;
 use nom::sequence::{preceded, tuple};
 use nom::IResult;
 use std::collections::HashMap;
 
 #[aoc_generator(day23)]
-fn parse_instructions(input: &str) -> Vec<Instruction> {
-    instructions(input).unwrap().1
+pub fn try_parse_instructions(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse::lines(23, input, instruction)
 }
 #[aoc(day23, part1)]
-fn solve_part1(instructions: &[Instruction]) -> u32 {
+pub fn solve_part1(instructions: &[Instruction]) -> u32 {
     compute(instructions, [('a', 0), ('b', 0)])
 }
 
 #[aoc(day23, part2)]
-fn solve_part2(instructions: &[Instruction]) -> u32 {
+pub fn solve_part2(instructions: &[Instruction]) -> u32 {
     compute(instructions, [('a', 1), ('b', 0)])
 }
 
@@ -54,9 +54,6 @@ fn compute(instructions: &[Instruction], initial_registers: [(char, u32); 2]) ->
     *registers.get(&'b').unwrap()
 }
 
-fn instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
-    separated_list1(line_ending, instruction)(input)
-}
 fn instruction(input: &str) -> IResult<&str, Instruction> {
     alt((increment, triple, half, jump, jump_if_even, jump_if_one))(input)
 }
@@ -89,7 +86,7 @@ fn jump_if_one(input: &str) -> IResult<&str, Instruction> {
 }
 
 #[derive(Debug, Eq, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     Increment(char),
     Triple(char),
     Half(char),
@@ -101,7 +98,9 @@ enum Instruction {
 #[cfg(test)]
 mod test {
     use crate::day23::Instruction::{Half, Increment, Jump, JumpIfEven, JumpIfOne, Triple};
-    use crate::day23::{half, increment, instructions, jump, jump_if_even, jump_if_one, triple};
+    use crate::day23::{
+        half, increment, jump, jump_if_even, jump_if_one, triple, try_parse_instructions,
+    };
 
     const EXAMPLE: &str = r"inc a
 jio a, +2
@@ -110,7 +109,7 @@ inc a";
 
     #[test]
     fn parse_instructions() {
-        let instructions = instructions(EXAMPLE).unwrap().1;
+        let instructions = try_parse_instructions(EXAMPLE).unwrap();
         assert_eq!(4, instructions.len())
     }
     #[test]
@@ -1,26 +1,21 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use nom::character::complete::{line_ending, u64 as u64_nom};
-use nom::multi::separated_list1;
-use nom::IResult;
+use nom::character::complete::u64 as u64_nom;
 use std::ops::{Add, Mul};
 
 #[aoc_generator(day24)]
-fn parse_packages(input: &str) -> Vec<u64> {
-    packages(input).unwrap().1
-}
-
-fn packages(input: &str) -> IResult<&str, Vec<u64>> {
-    separated_list1(line_ending, u64_nom)(input)
+pub fn try_parse_packages(input: &str) -> Result<Vec<u64>, ParseError> {
+    parse::lines(24, input, u64_nom)
 }
 
 #[aoc(day24, part1)]
-fn solve_part1(packages: &[u64]) -> u64 {
+pub fn solve_part1(packages: &[u64]) -> u64 {
     min_quantum_entanglement(3, packages).unwrap()
 }
 
 #[aoc(day24, part2)]
-fn solve_part2(packages: &[u64]) -> u64 {
+pub fn solve_part2(packages: &[u64]) -> u64 {
     min_quantum_entanglement(4, packages).unwrap()
 }
 
@@ -46,7 +41,7 @@ fn min_quantum_entanglement(partitions: u64, weights: &[u64]) -> Option<u64> {
     })
 }
 
-fn can_partition(weights: &Vec<u64>, partitions: u64) -> bool {
+fn can_partition(weights: &[u64], partitions: u64) -> bool {
     let total: u64 = weights.iter().sum();
     total % partitions == 0
         && backtrack(
@@ -88,7 +83,7 @@ fn backtrack(
     }
 }
 
-fn remaining(combo: &Vec<&u64>, packages: &[u64]) -> Vec<u64> {
+fn remaining(combo: &[&u64], packages: &[u64]) -> Vec<u64> {
     let mut remaining = packages.to_vec();
     for &item in combo {
         remaining.remove(remaining.iter().find_position(|&p| p == item).unwrap().0);
@@ -113,6 +108,6 @@ mod test {
             2u64, 3, 5, 7, 13, 17, 19, 23, 29, 31, 37, 41, 43, 53, 59, 61, 67, 71, 73, 83, 89, 97,
             101,
         ];
-        assert_eq!(true, can_partition(&weights.to_vec(), 2))
+        assert!(can_partition(&weights, 2))
     }
 }
@@ -1,3 +1,4 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::bytes::complete::tag;
 use nom::character::complete::u64 as u64_nom;
@@ -7,12 +8,12 @@ use nom::IResult;
 use std::ops::{Add, Div, Mul};
 
 #[aoc_generator(day25)]
-fn parse_row_column(input: &str) -> Coordinate {
-    coordinate(input).unwrap().1
+pub fn try_parse_row_column(input: &str) -> Result<Coordinate, ParseError> {
+    parse::complete(25, input, coordinate)
 }
 
 #[aoc(day25, part1)]
-fn solve_part1(coordinate: &Coordinate) -> u64 {
+pub fn solve_part1(coordinate: &Coordinate) -> u64 {
     let max = find_iteration(coordinate.row, coordinate.column);
 
     (1..max).fold(20151125u64, |code, _| {
@@ -57,7 +58,7 @@ fn coordinate(input: &str) -> IResult<&str, Coordinate> {
     )(input)
 }
 
-struct Coordinate {
+pub struct Coordinate {
     row: u64,
     column: u64,
 }
@@ -1,14 +1,22 @@
+use crate::grid::{Point, SparseGrid};
+use crate::stream::{invalid_byte, try_for_each_byte};
 use aoc_runner_derive::aoc;
-use std::collections::BTreeSet;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead};
+use std::ops::ControlFlow;
 
 #[aoc(day3, part1)]
 pub fn solve_part1(input: &str) -> usize {
     let start: House = (0, 0);
-    let mut visited: BTreeSet<House> = BTreeSet::from([start]);
+    let mut visited: SparseGrid<u32> = SparseGrid::new();
+    *visited.entry(start) += 1;
 
     input.chars().fold(start, |house, c| {
         let position = next_house(house, c);
-        visited.insert(position);
+        *visited.entry(position) += 1;
         position
     });
     visited.len()
@@ -17,7 +25,8 @@ pub fn solve_part1(input: &str) -> usize {
 #[aoc(day3, part2)]
 pub fn solve_part2(input: &str) -> usize {
     let start: House = (0, 0);
-    let mut visited: BTreeSet<House> = BTreeSet::from([start]);
+    let mut visited: SparseGrid<u32> = SparseGrid::new();
+    *visited.entry(start) += 1;
 
     let mut santa = start;
     let mut robos = start;
@@ -25,17 +34,17 @@ pub fn solve_part2(input: &str) -> usize {
     input.chars().enumerate().for_each(|(i, direction)| {
         if i % 2 == 1 {
             santa = next_house(santa, direction);
-            visited.insert(santa);
+            *visited.entry(santa) += 1;
         } else {
             robos = next_house(robos, direction);
-            visited.insert(robos);
+            *visited.entry(robos) += 1;
         }
     });
 
     visited.len()
 }
 
-type House = (i32, i32);
+pub type House = Point;
 
 fn next_house((x, y): House, direction: char) -> House {
     match direction {
@@ -46,3 +55,430 @@ fn next_house((x, y): House, direction: char) -> House {
         _ => panic!("Unexpected direction"),
     }
 }
+
+/// Streaming [solve_part1], ignoring whitespace such as a trailing newline.
+pub fn houses_from_reader(reader: impl BufRead) -> io::Result<usize> {
+    houses_visited(reader, 1)
+}
+
+/// Streaming [solve_part2], ignoring whitespace such as a trailing newline.
+pub fn robo_houses_from_reader(reader: impl BufRead) -> io::Result<usize> {
+    houses_visited(reader, 2)
+}
+
+/// Houses visited by `agents` taking turns to follow the directions.
+fn houses_visited(reader: impl BufRead, agents: usize) -> io::Result<usize> {
+    let start: House = (0, 0);
+    let mut visited: SparseGrid<u32> = SparseGrid::new();
+    *visited.entry(start) += 1;
+
+    let mut positions = vec![start; agents];
+    let mut position = 0;
+    let mut moves = 0;
+    try_for_each_byte(reader, |byte| {
+        position += 1;
+        if !byte.is_ascii_whitespace() {
+            if !matches!(byte, b'^' | b'>' | b'<' | b'v') {
+                return Err(invalid_byte(byte, position));
+            }
+            let agent = &mut positions[moves % agents];
+            *agent = next_house(*agent, byte as char);
+            *visited.entry(*agent) += 1;
+            moves += 1;
+        }
+        Ok(ControlFlow::<()>::Continue(()))
+    })?;
+    Ok(visited.len())
+}
+
+/// How the directions are shared out between the agents.
+#[derive(Debug, Clone, Copy)]
+pub enum Strategy {
+    /// Agents take turns, one direction each.
+    RoundRobin,
+    /// Agents take turns following `n` consecutive directions each.
+    Blocks(usize),
+    /// `f(index, agents)` picks the agent following the direction at `index`.
+    Custom(fn(usize, usize) -> usize),
+}
+
+impl Strategy {
+    fn agent(&self, index: usize, agents: usize) -> usize {
+        match self {
+            Strategy::RoundRobin => index % agents,
+            Strategy::Blocks(n) => index / n % agents,
+            Strategy::Custom(f) => f(index, agents) % agents,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum DeliveryError {
+    /// A character other than `^`, `>`, `<` or `v` at this 1-based position.
+    Direction { direction: char, position: usize },
+    /// Nobody to follow the directions.
+    NoAgents,
+    /// [Strategy::Blocks] of no directions.
+    EmptyBlocks,
+}
+
+impl Display for DeliveryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryError::Direction {
+                direction,
+                position,
+            } => write!(
+                f,
+                "unexpected direction {direction:?} at position {position}"
+            ),
+            DeliveryError::NoAgents => write!(f, "a delivery needs at least one agent"),
+            DeliveryError::EmptyBlocks => write!(f, "blocks need at least one direction each"),
+        }
+    }
+}
+
+impl Error for DeliveryError {}
+
+/// Presents delivered by any number of agents starting together at `(0, 0)`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Delivery {
+    visits: SparseGrid<u32>,
+    paths: Vec<Vec<House>>,
+}
+
+impl Delivery {
+    /// Follows `directions`, ignoring whitespace, with `agents` agents sharing them by `strategy`.
+    pub fn simulate(
+        directions: &str,
+        agents: usize,
+        strategy: Strategy,
+    ) -> Result<Delivery, DeliveryError> {
+        if agents == 0 {
+            return Err(DeliveryError::NoAgents);
+        }
+        if let Strategy::Blocks(0) = strategy {
+            return Err(DeliveryError::EmptyBlocks);
+        }
+        let start: House = (0, 0);
+        let mut visits = SparseGrid::new();
+        *visits.entry(start) += agents as u32;
+        let mut paths = vec![vec![start]; agents];
+
+        let moves = directions
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace());
+        for (index, (position, direction)) in moves.enumerate() {
+            if !matches!(direction, '^' | '>' | '<' | 'v') {
+                return Err(DeliveryError::Direction {
+                    direction,
+                    position: position + 1,
+                });
+            }
+            let path = &mut paths[strategy.agent(index, agents)];
+            let house = next_house(*path.last().unwrap(), direction);
+            *visits.entry(house) += 1;
+            path.push(house);
+        }
+        Ok(Delivery { visits, paths })
+    }
+
+    /// Presents delivered to every visited house, including those at the start.
+    pub fn visits(&self) -> &SparseGrid<u32> {
+        &self.visits
+    }
+
+    /// Houses visited by each agent in order, starting at `(0, 0)`.
+    pub fn paths(&self) -> &[Vec<House>] {
+        &self.paths
+    }
+
+    pub fn houses(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn presents(&self, house: House) -> u32 {
+        self.visits.get(house).copied().unwrap_or(0)
+    }
+
+    /// House with the most presents, the smallest one on ties.
+    pub fn most_presents(&self) -> Option<(House, u32)> {
+        self.visits
+            .iter()
+            .map(|(house, &presents)| (house, presents))
+            .min_by_key(|&(house, presents)| (Reverse(presents), house))
+    }
+
+    /// Houses that exactly `k` distinct agents delivered to, sorted.
+    pub fn visited_by_exactly(&self, k: usize) -> Vec<House> {
+        let mut agents: SparseGrid<usize> = SparseGrid::new();
+        for path in &self.paths {
+            for house in path.iter().collect::<HashSet<_>>() {
+                *agents.entry(*house) += 1;
+            }
+        }
+        let mut houses: Vec<House> = agents
+            .iter()
+            .filter(|&(_, &count)| count == k)
+            .map(|(house, _)| house)
+            .collect();
+        houses.sort();
+        houses
+    }
+}
+
+pub type Rgb = [u8; 3];
+
+/// Colors for the houses in a rendered [Delivery], unvisited houses are white.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Palette {
+    /// From pale yellow for a single present to dark red for the most presents.
+    Heat,
+    /// Blend of the colors of every agent visiting a house, agent `i` taking color `i` modulo
+    /// the number of colors.
+    Agents(Vec<Rgb>),
+}
+
+pub const AGENT_COLORS: [Rgb; 6] = [
+    [228, 26, 28],
+    [55, 126, 184],
+    [77, 175, 74],
+    [152, 78, 163],
+    [255, 127, 0],
+    [166, 86, 40],
+];
+
+const BACKGROUND: Rgb = [255, 255, 255];
+const FEW_PRESENTS: Rgb = [255, 237, 160];
+const MANY_PRESENTS: Rgb = [189, 0, 38];
+
+impl Delivery {
+    /// Plain (`P3`) PPM image of the bounding box of visited houses, north up.
+    pub fn to_ppm(&self, palette: &Palette) -> String {
+        let (width, height, pixels) = self.pixels(palette);
+        let mut ppm = format!("P3\n{width} {height}\n255\n");
+        for row in pixels.chunks(width) {
+            let row: Vec<String> = row.iter().map(|[r, g, b]| format!("{r} {g} {b}")).collect();
+            ppm.push_str(&row.join(" "));
+            ppm.push('\n');
+        }
+        ppm
+    }
+
+    /// SVG image of the bounding box of visited houses, north up, `scale` pixels per house.
+    pub fn to_svg(&self, palette: &Palette, scale: usize) -> String {
+        let (width, height, pixels) = self.pixels(palette);
+        let mut svg = vec![
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#,
+                width * scale,
+                height * scale
+            ),
+            format!(
+                r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+                hex(BACKGROUND)
+            ),
+        ];
+        for (idx, &color) in pixels.iter().enumerate() {
+            if color != BACKGROUND {
+                svg.push(format!(
+                    r#"<rect x="{}" y="{}" width="1" height="1" fill="{}"/>"#,
+                    idx % width,
+                    idx / width,
+                    hex(color)
+                ));
+            }
+        }
+        svg.push("</svg>".to_string());
+        svg.join("\n")
+    }
+
+    /// Row-major colors of the bounding box, from the northernmost row down.
+    fn pixels(&self, palette: &Palette) -> (usize, usize, Vec<Rgb>) {
+        // Houses are `(x, y)` with `y` growing northwards, unlike the `(row, column)` of a grid.
+        let bounds = self.visits.bounds().unwrap();
+        let (west, east) = (bounds.from.0, bounds.to.0);
+        let (south, north) = (bounds.from.1, bounds.to.1);
+        let (width, height) = ((east - west + 1) as usize, (north - south + 1) as usize);
+        let mut pixels = vec![BACKGROUND; width * height];
+        let index = |(x, y): House| (north - y) as usize * width + (x - west) as usize;
+        match palette {
+            Palette::Heat => {
+                let most = self.visits.iter().map(|(_, &n)| n).max().unwrap_or(1);
+                for (house, &presents) in self.visits.iter() {
+                    pixels[index(house)] = blend(FEW_PRESENTS, MANY_PRESENTS, presents, most);
+                }
+            }
+            Palette::Agents(colors) => {
+                let mut agents: SparseGrid<Vec<usize>> = SparseGrid::new();
+                for (agent, path) in self.paths.iter().enumerate() {
+                    for &house in path {
+                        let visitors = agents.entry(house);
+                        if visitors.last() != Some(&agent) {
+                            visitors.push(agent);
+                        }
+                    }
+                }
+                for (house, visitors) in agents.iter() {
+                    let [r, g, b] = visitors.iter().fold([0; 3], |sum, agent| {
+                        let color = colors.get(agent % colors.len().max(1)).unwrap_or(&[0; 3]);
+                        [0, 1, 2].map(|c| sum[c] + color[c] as usize)
+                    });
+                    let n = visitors.len();
+                    pixels[index(house)] = [(r / n) as u8, (g / n) as u8, (b / n) as u8];
+                }
+            }
+        }
+        (width, height, pixels)
+    }
+}
+
+/// Color for `n` presents out of `most`, from `low` at one present to `high` at `most`.
+fn blend(low: Rgb, high: Rgb, n: u32, most: u32) -> Rgb {
+    if most <= 1 {
+        return high;
+    }
+    let (n, most) = (n as i32 - 1, most as i32 - 1);
+    [0, 1, 2].map(|c| (low[c] as i32 + (high[c] as i32 - low[c] as i32) * n / most) as u8)
+}
+
+fn hex([r, g, b]: Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::day3::{
+        houses_from_reader, robo_houses_from_reader, solve_part1, solve_part2, Delivery,
+        DeliveryError, Palette, Strategy, AGENT_COLORS,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(2, solve_part1(">"));
+        assert_eq!(4, solve_part1("^>v<"));
+        assert_eq!(2, solve_part1("^v^v^v^v^v"));
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(3, solve_part2("^v"));
+        assert_eq!(3, solve_part2("^>v<"));
+        assert_eq!(11, solve_part2("^v^v^v^v^v"));
+    }
+
+    #[test]
+    fn streaming() {
+        for input in [">", "^>v<", "^v^v^v^v^v", "^^>>vv<<<v"] {
+            let bytes = format!("{input}\n").into_bytes();
+            assert_eq!(
+                solve_part1(input),
+                houses_from_reader(BufReader::with_capacity(3, bytes.as_slice())).unwrap()
+            );
+            assert_eq!(
+                solve_part2(input),
+                robo_houses_from_reader(BufReader::with_capacity(3, bytes.as_slice())).unwrap()
+            );
+        }
+        assert!(houses_from_reader("^x".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn delivery_matches_solvers() {
+        for input in [">", "^>v<", "^v^v^v^v^v", "^^>>vv<<<v"] {
+            assert_eq!(
+                solve_part1(input),
+                Delivery::simulate(input, 1, Strategy::RoundRobin)
+                    .unwrap()
+                    .houses()
+            );
+            assert_eq!(
+                solve_part2(input),
+                Delivery::simulate(input, 2, Strategy::RoundRobin)
+                    .unwrap()
+                    .houses()
+            );
+        }
+    }
+
+    #[test]
+    fn delivery_strategies() {
+        let blocks = Delivery::simulate("^^vv>>", 3, Strategy::Blocks(2)).unwrap();
+        assert_eq!(
+            vec![
+                vec![(0, 0), (0, 1), (0, 2)],
+                vec![(0, 0), (0, -1), (0, -2)],
+                vec![(0, 0), (1, 0), (2, 0)],
+            ],
+            blocks.paths()
+        );
+        assert_eq!(Some(((0, 0), 3)), blocks.most_presents());
+        assert_eq!(vec![(0, 0)], blocks.visited_by_exactly(3));
+        assert!(blocks.visited_by_exactly(2).is_empty());
+
+        let lazy = Delivery::simulate("^>v<", 2, Strategy::Custom(|_, _| 1)).unwrap();
+        assert_eq!(1, lazy.paths()[0].len());
+        assert_eq!(5, lazy.paths()[1].len());
+        assert_eq!(3, lazy.presents((0, 0)));
+        assert_eq!(3, lazy.visited_by_exactly(1).len());
+    }
+
+    #[test]
+    fn delivery_errors() {
+        assert_eq!(
+            Some(DeliveryError::Direction {
+                direction: 'x',
+                position: 4
+            }),
+            Delivery::simulate("^ >x", 1, Strategy::RoundRobin).err()
+        );
+        assert_eq!(
+            Some(DeliveryError::NoAgents),
+            Delivery::simulate("^>", 0, Strategy::RoundRobin).err()
+        );
+        assert_eq!(
+            Some(DeliveryError::EmptyBlocks),
+            Delivery::simulate("^>", 2, Strategy::Blocks(0)).err()
+        );
+    }
+
+    #[test]
+    fn heatmap_ppm() {
+        let delivery = Delivery::simulate("^v^v>", 1, Strategy::RoundRobin).unwrap();
+        assert_eq!(
+            "P3\n2 2\n255\n222 119 99 255 255 255\n189 0 38 255 237 160\n",
+            delivery.to_ppm(&Palette::Heat)
+        );
+    }
+
+    #[test]
+    fn non_square_ppm() {
+        let wide = Delivery::simulate(">>", 1, Strategy::RoundRobin)
+            .unwrap()
+            .to_ppm(&Palette::Heat);
+        assert!(wide.starts_with("P3\n3 1\n255\n"));
+        assert_eq!(4, wide.lines().count());
+        assert_eq!(9, wide.lines().last().unwrap().split(' ').count());
+
+        let tall = Delivery::simulate("^^", 1, Strategy::RoundRobin)
+            .unwrap()
+            .to_ppm(&Palette::Heat);
+        assert!(tall.starts_with("P3\n1 3\n255\n"));
+        assert_eq!(6, tall.lines().count());
+    }
+
+    #[test]
+    fn agents_svg() {
+        let delivery = Delivery::simulate("^>", 2, Strategy::RoundRobin).unwrap();
+        let svg = delivery.to_svg(&Palette::Agents(AGENT_COLORS.to_vec()), 10);
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20""#)
+        );
+        assert!(svg.contains(r##"<rect x="0" y="0" width="1" height="1" fill="#e41a1c"/>"##));
+        assert!(svg.contains(r##"<rect x="1" y="1" width="1" height="1" fill="#377eb8"/>"##));
+        assert!(svg.contains(r##"<rect x="0" y="1" width="1" height="1" fill="#8d4c6a"/>"##));
+        assert_eq!(4, svg.matches("<rect").count());
+    }
+}
@@ -1,22 +1,238 @@
 use aoc_runner_derive::aoc;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use std::{fs, io, thread};
 
 #[aoc(day4, part1)]
 pub fn solve_part1(input: &str) -> u64 {
-    (0u64..)
-        .find(|i| matches!(md5::compute(format!("{input}{i}")).0, [0, 0, third, ..] if third <= 15))
-        .unwrap()
+    mine(input, 5, available_threads())
 }
 
 #[aoc(day4, part2)]
 pub fn solve_part2(input: &str) -> u64 {
+    mine(input, 6, available_threads())
+}
+
+/// [solve_part1] hashing a freshly formatted string per nonce, kept for comparison.
+#[aoc(day4, part1, Format)]
+pub fn solve_part1_format(input: &str) -> u64 {
     (0u64..)
-        .find(|i| matches!(md5::compute(format!("{input}{i}")).0, [0, 0, 0, ..]))
+        .find(|nonce| has_leading_zeros(&md5::compute(format!("{input}{nonce}")).0, 5))
         .unwrap()
 }
 
+fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Nonces handed to a thread at a time.
+const BATCH: u64 = 10_000;
+/// Batches per thread between two progress reports.
+const BATCHES_PER_ROUND: u64 = 16;
+
+/// Lowest nonce whose hash with `secret` starts with `leading_zero_nibbles` zero hex digits.
+///
+/// Threads take batches of nonces in increasing order and stop once every batch below the best
+/// nonce found so far is taken, so the answer does not depend on `threads`.
+pub fn mine(secret: &str, leading_zero_nibbles: usize, threads: usize) -> u64 {
+    Session::new(secret, leading_zero_nibbles)
+        .run(threads, &AtomicBool::new(false), |_, _| {})
+        .unwrap()
+}
+
+/// Lowest matching nonce in `from..to`, possibly not the lowest once `cancel` is set.
+fn search(
+    hasher: &NonceHasher,
+    leading_zero_nibbles: usize,
+    (from, to): (u64, u64),
+    threads: usize,
+    cancel: &AtomicBool,
+) -> Option<u64> {
+    let next = AtomicU64::new(from);
+    let best = AtomicU64::new(u64::MAX);
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| loop {
+                let start = next.fetch_add(BATCH, Ordering::Relaxed);
+                if start >= to.min(best.load(Ordering::Relaxed)) || cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+                if let Some(nonce) = (start..(start + BATCH).min(to))
+                    .find(|&nonce| has_leading_zeros(&hasher.digest(nonce), leading_zero_nibbles))
+                {
+                    best.fetch_min(nonce, Ordering::Relaxed);
+                    return;
+                }
+            });
+        }
+    });
+    Some(best.into_inner()).filter(|&nonce| nonce != u64::MAX)
+}
+
+/// Reported by [Session::run] after every round of batches.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Progress {
+    /// Every nonce below this one has been checked.
+    pub nonce: u64,
+    /// Nonces checked since the run started.
+    pub checked: u64,
+    pub elapsed: Duration,
+    pub nonces_per_second: f64,
+}
+
+/// A search for the lowest nonce that can be cancelled, saved and resumed.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Session {
+    secret: String,
+    leading_zero_nibbles: usize,
+    next: u64,
+    found: Option<u64>,
+}
+
+impl Session {
+    pub fn new(secret: &str, leading_zero_nibbles: usize) -> Self {
+        Session {
+            secret: secret.to_string(),
+            leading_zero_nibbles,
+            next: 0,
+            found: None,
+        }
+    }
+
+    /// Every nonce below this one has been checked.
+    pub fn next_nonce(&self) -> u64 {
+        self.next
+    }
+
+    pub fn found(&self) -> Option<u64> {
+        self.found
+    }
+
+    /// Searches on from the last checked nonce until a match is found or `cancel` is set, in
+    /// which case the session can be run again (or saved) to resume the search.
+    ///
+    /// `progress` also gets the session as of the report, which can be saved as a checkpoint.
+    pub fn run(
+        &mut self,
+        threads: usize,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(&Progress, &Session),
+    ) -> Option<u64> {
+        let hasher = NonceHasher::new(&self.secret);
+        let threads = threads.max(1);
+        let round = threads as u64 * BATCH * BATCHES_PER_ROUND;
+        let (first, start) = (self.next, Instant::now());
+        while self.found.is_none() && !cancel.load(Ordering::Relaxed) {
+            let to = self.next.saturating_add(round);
+            let found = search(
+                &hasher,
+                self.leading_zero_nibbles,
+                (self.next, to),
+                threads,
+                cancel,
+            );
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            match found {
+                Some(nonce) => {
+                    self.found = Some(nonce);
+                    self.next = nonce + 1;
+                }
+                None => self.next = to,
+            }
+            let elapsed = start.elapsed();
+            let report = Progress {
+                nonce: self.next,
+                checked: self.next - first,
+                elapsed,
+                nonces_per_second: (self.next - first) as f64 / elapsed.as_secs_f64().max(1e-9),
+            };
+            progress(&report, self);
+        }
+        self.found
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "secret": self.secret,
+            "leading_zero_nibbles": self.leading_zero_nibbles,
+            "next": self.next,
+            "found": self.found,
+        })
+    }
+
+    pub fn from_json(json: &Value) -> Option<Session> {
+        Some(Session {
+            secret: json["secret"].as_str()?.to_string(),
+            leading_zero_nibbles: json["leading_zero_nibbles"].as_u64()? as usize,
+            next: json["next"].as_u64()?,
+            found: match &json["found"] {
+                Value::Null => None,
+                found => Some(found.as_u64()?),
+            },
+        })
+    }
+
+    /// Writes a checkpoint to resume from with [Session::load].
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json().to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Session> {
+        let json: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Session::from_json(&json)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed day 4 checkpoint"))
+    }
+}
+
+/// Hashes a secret followed by decimal nonces, absorbing the secret only once.
+#[derive(Clone)]
+pub struct NonceHasher {
+    prefix: md5::Context,
+}
+
+impl NonceHasher {
+    pub fn new(secret: &str) -> Self {
+        let mut prefix = md5::Context::new();
+        prefix.consume(secret);
+        NonceHasher { prefix }
+    }
+
+    pub fn digest(&self, nonce: u64) -> [u8; 16] {
+        let mut buffer = [0; 20];
+        let mut context = self.prefix.clone();
+        context.consume(decimal(nonce, &mut buffer));
+        context.compute().0
+    }
+}
+
+/// Writes `n` in decimal at the end of `buffer`, which fits any `u64`.
+fn decimal(mut n: u64, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut start = buffer.len();
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buffer[start..];
+        }
+    }
+}
+
+pub fn has_leading_zeros(digest: &[u8; 16], nibbles: usize) -> bool {
+    let bytes = nibbles.min(32) / 2;
+    digest[..bytes].iter().all(|&byte| byte == 0)
+        && (nibbles % 2 == 0 || nibbles >= 32 || digest[bytes] <= 0x0f)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::day4::solve_part1;
+    use crate::day4::{decimal, has_leading_zeros, mine, solve_part1, NonceHasher, Session};
+    use std::env;
+    use std::sync::atomic::{AtomicBool, Ordering};
 
     #[test]
     fn part1_example1() {
@@ -27,4 +243,113 @@ mod test {
     fn part1_example2() {
         assert_eq!(1048970, solve_part1("pqrstuv"));
     }
+
+    #[test]
+    fn difficulty() {
+        let mut digest = [0xff; 16];
+        assert!(has_leading_zeros(&digest, 0));
+        assert!(!has_leading_zeros(&digest, 1));
+        digest[..2].copy_from_slice(&[0x00, 0x0a]);
+        assert!(has_leading_zeros(&digest, 3));
+        assert!(!has_leading_zeros(&digest, 4));
+    }
+
+    #[test]
+    fn deterministic_across_threads() {
+        let lowest = mine("abcdef", 4, 1);
+        assert_eq!(31556, lowest);
+        for threads in [2, 3, 8] {
+            assert_eq!(lowest, mine("abcdef", 4, threads));
+        }
+        assert_eq!(609043, mine("abcdef", 5, 3));
+    }
+
+    #[test]
+    fn nonce_hasher() {
+        let mut buffer = [0; 20];
+        assert_eq!(b"0", decimal(0, &mut buffer));
+        assert_eq!(b"609043", decimal(609043, &mut buffer));
+        assert_eq!(
+            u64::MAX.to_string().as_bytes(),
+            decimal(u64::MAX, &mut buffer)
+        );
+
+        let hasher = NonceHasher::new("abcdef");
+        for nonce in [0, 9, 10, 609043, u64::MAX] {
+            assert_eq!(
+                md5::compute(format!("abcdef{nonce}")).0,
+                hasher.digest(nonce)
+            );
+        }
+    }
+
+    #[test]
+    fn session_resumes_from_checkpoint() {
+        let cancel = AtomicBool::new(false);
+        let mut session = Session::new("abcdef", 5);
+        let mut reports = 0;
+        assert_eq!(
+            None,
+            session.run(2, &cancel, |progress, _| {
+                reports += 1;
+                assert!(progress.nonce > 0);
+                if progress.nonce >= 300_000 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            })
+        );
+        assert!(reports > 0);
+        let checked = session.next_nonce();
+        assert!((300_000..609043).contains(&checked));
+
+        let path = env::temp_dir().join(format!("day4-checkpoint-{}.json", std::process::id()));
+        session.save(&path).unwrap();
+        let mut restored = Session::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(session, restored);
+
+        let mut first = None;
+        let found = restored.run(3, &AtomicBool::new(false), |progress, _| {
+            first.get_or_insert(progress.checked);
+        });
+        assert_eq!(Some(609043), found);
+        assert_eq!(Some(609043), restored.found());
+        assert!(first.unwrap() <= 609043 - checked + 1);
+    }
+
+    #[test]
+    fn checkpoint_written_mid_run() {
+        let path = env::temp_dir().join(format!("day4-periodic-{}.json", std::process::id()));
+        let mut saved = None;
+        let found =
+            Session::new("abcdef", 5).run(1, &AtomicBool::new(false), |progress, session| {
+                if saved.is_none() && progress.nonce >= 300_000 {
+                    session.save(&path).unwrap();
+                    saved = Some(session.clone());
+                }
+            });
+        assert_eq!(Some(609043), found);
+
+        let mut restored = Session::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved, Some(restored.clone()));
+        assert_eq!(None, restored.found());
+        let checked = restored.next_nonce();
+        assert!((300_000..609043).contains(&checked));
+
+        let mut first = None;
+        let found = restored.run(2, &AtomicBool::new(false), |progress, _| {
+            first.get_or_insert(progress.checked);
+        });
+        assert_eq!(Some(609043), found);
+        assert!(first.unwrap() <= 609043 - checked + 1);
+    }
+
+    #[test]
+    fn malformed_checkpoint() {
+        assert_eq!(
+            None,
+            Session::from_json(&serde_json::json!({"secret": "abcdef", "next": 7}))
+        );
+    }
 }
@@ -1,9 +1,26 @@
+use crate::parse::{self, ParseError};
+use crate::stream::for_each_line;
 use aoc_runner_derive::aoc;
 use itertools::Itertools;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, char, space0, space1, u32 as nom_u32};
+use nom::combinator::{cut, map};
+use nom::multi::{many0, separated_list1};
+use nom::sequence::{delimited, pair, preceded, tuple};
+use nom::IResult;
 use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::{self, BufRead};
 
 #[aoc(day5, part1)]
 pub fn solve_part1(input: &str) -> usize {
+    input.lines().filter(|word| classify(word).nice()).count()
+}
+
+/// [solve_part1] checking every rule separately.
+#[aoc(day5, part1, Filters)]
+pub fn solve_part1_filters(input: &str) -> usize {
     input
         .lines()
         .filter(at_least_3_vowels)
@@ -31,6 +48,12 @@ fn not_restricted(text: &&str) -> bool {
 
 #[aoc(day5, part2)]
 pub fn solve_part2(input: &str) -> usize {
+    input.lines().filter(|word| classify(word).nicer()).count()
+}
+
+/// [solve_part2] checking every rule separately.
+#[aoc(day5, part2, Filters)]
+pub fn solve_part2_filters(input: &str) -> usize {
     input
         .lines()
         .filter(paired_twice)
@@ -40,6 +63,98 @@ pub fn solve_part2(input: &str) -> usize {
 
 type CharPair = (char, char);
 
+/// Every predicate of both parts, see [classify].
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct Classification {
+    pub three_vowels: bool,
+    pub double: bool,
+    pub not_restricted: bool,
+    pub paired_twice: bool,
+    pub paired_triplet: bool,
+}
+
+impl Classification {
+    pub fn nice(&self) -> bool {
+        self.three_vowels && self.double && self.not_restricted
+    }
+
+    pub fn nicer(&self) -> bool {
+        self.paired_twice && self.paired_triplet
+    }
+}
+
+/// Evaluates all the rules in a single pass over `word` without allocating, remembering where
+/// each pair of lowercase letters first started in a 26×26 table. Words with anything but
+/// lowercase ASCII letters, which the puzzle input never has, are checked rule by rule instead.
+pub fn classify(word: &str) -> Classification {
+    if !word.bytes().all(|byte| byte.is_ascii_lowercase()) {
+        return Classification {
+            three_vowels: at_least_3_vowels(&word),
+            double: duplicate_letters(&word),
+            not_restricted: not_restricted(&word),
+            paired_twice: paired_twice(&word),
+            paired_triplet: paired_triplet(&word),
+        };
+    }
+    let word = word.as_bytes();
+    let mut first_pair = [[usize::MAX; 26]; 26];
+    let mut vowels = 0;
+    let mut result = Classification {
+        not_restricted: true,
+        ..Classification::default()
+    };
+    for (idx, &byte) in word.iter().enumerate() {
+        if matches!(byte, b'a' | b'e' | b'i' | b'o' | b'u') {
+            vowels += 1;
+        }
+        if idx >= 2 && word[idx - 2] == byte {
+            result.paired_triplet = true;
+        }
+        if idx == 0 {
+            continue;
+        }
+        let previous = word[idx - 1];
+        if previous == byte {
+            result.double = true;
+        }
+        if matches!(
+            (previous, byte),
+            (b'a', b'b') | (b'c', b'd') | (b'p', b'q') | (b'x', b'y')
+        ) {
+            result.not_restricted = false;
+        }
+        let first = &mut first_pair[(previous - b'a') as usize][(byte - b'a') as usize];
+        let start = idx - 1;
+        if *first == usize::MAX {
+            *first = start;
+        } else if start - *first >= 2 {
+            result.paired_twice = true;
+        }
+    }
+    result.three_vowels = vowels >= 3;
+    result
+}
+
+/// Streaming [solve_part1], reading one line at a time.
+pub fn count_nice_from_reader(reader: impl BufRead) -> io::Result<usize> {
+    count_from_reader(reader, Classification::nice)
+}
+
+/// Streaming [solve_part2], reading one line at a time.
+pub fn count_nicer_from_reader(reader: impl BufRead) -> io::Result<usize> {
+    count_from_reader(reader, Classification::nicer)
+}
+
+fn count_from_reader(reader: impl BufRead, rule: fn(&Classification) -> bool) -> io::Result<usize> {
+    let mut count = 0;
+    for_each_line(reader, |line| {
+        if !line.is_empty() && rule(&classify(line)) {
+            count += 1;
+        }
+    })?;
+    Ok(count)
+}
+
 fn paired_twice(text: &&str) -> bool {
     let mut pairs: HashMap<CharPair, usize> = HashMap::new();
 
@@ -59,31 +174,259 @@ fn paired_triplet(text: &&str) -> bool {
         .any(|w| w.len() == 3 && w[0] == w[2])
 }
 
+/// [solve_part1] through the equivalent [RuleSet].
+#[aoc(day5, part1, Rules)]
+pub fn solve_part1_rules(input: &str) -> usize {
+    RuleSet::nice().count(input)
+}
+
+/// [solve_part2] through the equivalent [RuleSet].
+#[aoc(day5, part2, Rules)]
+pub fn solve_part2_rules(input: &str) -> usize {
+    RuleSet::nicer().count(input)
+}
+
+/// A single requirement for a word to be nice.
+pub trait Rule: Debug + Send + Sync {
+    /// The rule as written in a rule set description.
+    fn describe(&self) -> String;
+
+    /// Whether `word` passes, along with the character positions that decided it: the matches
+    /// of a rule requiring something, or the offenders of a rule forbidding something.
+    fn check(&self, word: &str) -> (bool, Vec<usize>);
+}
+
+/// `vowels>=n`: at least `n` vowels.
+#[derive(Debug)]
+pub struct Vowels(pub usize);
+
+/// `double`: a letter appearing twice in a row.
+#[derive(Debug)]
+pub struct Double;
+
+/// `forbid ab,cd`: none of the listed substrings.
+#[derive(Debug)]
+pub struct Forbid(pub Vec<String>);
+
+/// `pair-twice`: a pair of letters appearing twice without overlapping.
+#[derive(Debug)]
+pub struct PairTwice;
+
+/// `sandwich`: a letter repeated with exactly one letter in between.
+#[derive(Debug)]
+pub struct Sandwich;
+
+impl Rule for Vowels {
+    fn describe(&self) -> String {
+        format!("vowels>={}", self.0)
+    }
+
+    fn check(&self, word: &str) -> (bool, Vec<usize>) {
+        let vowels = positions(word, |_, c| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u'));
+        (vowels.len() >= self.0, vowels)
+    }
+}
+
+impl Rule for Double {
+    fn describe(&self) -> String {
+        "double".to_string()
+    }
+
+    fn check(&self, word: &str) -> (bool, Vec<usize>) {
+        let chars: Vec<char> = word.chars().collect();
+        let doubles = positions(word, |idx, c| chars.get(idx + 1) == Some(&c));
+        (!doubles.is_empty(), doubles)
+    }
+}
+
+impl Rule for Forbid {
+    fn describe(&self) -> String {
+        format!("forbid {}", self.0.join(","))
+    }
+
+    fn check(&self, word: &str) -> (bool, Vec<usize>) {
+        let chars: Vec<char> = word.chars().collect();
+        let offenders = positions(word, |idx, _| {
+            self.0.iter().any(|forbidden| {
+                forbidden
+                    .chars()
+                    .enumerate()
+                    .all(|(offset, c)| chars.get(idx + offset) == Some(&c))
+            })
+        });
+        (offenders.is_empty(), offenders)
+    }
+}
+
+impl Rule for PairTwice {
+    fn describe(&self) -> String {
+        "pair-twice".to_string()
+    }
+
+    fn check(&self, word: &str) -> (bool, Vec<usize>) {
+        let mut pairs: HashMap<CharPair, usize> = HashMap::new();
+        for (idx, pair) in word.chars().tuple_windows().enumerate() {
+            match pairs.get(&pair) {
+                Some(&first) if idx - first >= 2 => return (true, vec![first, idx]),
+                Some(_) => {}
+                None => {
+                    pairs.insert(pair, idx);
+                }
+            }
+        }
+        (false, vec![])
+    }
+}
+
+impl Rule for Sandwich {
+    fn describe(&self) -> String {
+        "sandwich".to_string()
+    }
+
+    fn check(&self, word: &str) -> (bool, Vec<usize>) {
+        let chars: Vec<char> = word.chars().collect();
+        let sandwiches = positions(word, |idx, c| chars.get(idx + 2) == Some(&c));
+        (!sandwiches.is_empty(), sandwiches)
+    }
+}
+
+/// Character positions in `word` satisfying `f(position, char)`.
+fn positions(word: &str, f: impl Fn(usize, char) -> bool) -> Vec<usize> {
+    word.chars()
+        .enumerate()
+        .filter(|&(idx, c)| f(idx, c))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Outcome of a single rule for [RuleSet::explain].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Verdict {
+    pub rule: String,
+    pub passed: bool,
+    pub positions: Vec<usize>,
+}
+
+/// Rules a word has to pass all of to be nice, composed at runtime from a description such as
+/// `vowels>=3; double; forbid ab,cd,pq,xy`.
+#[derive(Debug)]
+pub struct RuleSet {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<Box<dyn Rule>>) -> Self {
+        RuleSet { rules }
+    }
+
+    /// Parses `;` separated rules, see the [Rule] implementations for their syntax.
+    pub fn parse(description: &str) -> Result<RuleSet, ParseError> {
+        parse::complete(5, description, rule_set)
+    }
+
+    /// The rules of part 1.
+    pub fn nice() -> RuleSet {
+        RuleSet::parse("vowels>=3; double; forbid ab,cd,pq,xy").unwrap()
+    }
+
+    /// The rules of part 2.
+    pub fn nicer() -> RuleSet {
+        RuleSet::parse("pair-twice; sandwich").unwrap()
+    }
+
+    pub fn describe(&self) -> String {
+        self.rules.iter().map(|rule| rule.describe()).join("; ")
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        self.rules.iter().all(|rule| rule.check(word).0)
+    }
+
+    /// Nice words among the lines of `input`.
+    pub fn count(&self, input: &str) -> usize {
+        input.lines().filter(|word| self.matches(word)).count()
+    }
+
+    pub fn explain(&self, word: &str) -> Vec<Verdict> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                let (passed, positions) = rule.check(word);
+                Verdict {
+                    rule: rule.describe(),
+                    passed,
+                    positions,
+                }
+            })
+            .collect()
+    }
+}
+
+fn rule_set(input: &str) -> IResult<&str, RuleSet> {
+    map(
+        delimited(
+            space0,
+            pair(
+                rule,
+                many0(preceded(tuple((space0, char(';'), space0)), cut(rule))),
+            ),
+            space0,
+        ),
+        |(first, rest)| RuleSet::new(std::iter::once(first).chain(rest).collect()),
+    )(input)
+}
+
+fn rule(input: &str) -> IResult<&str, Box<dyn Rule>> {
+    alt((
+        map(
+            preceded(tuple((tag("vowels"), space0, tag(">="), space0)), nom_u32),
+            |n| Box::new(Vowels(n as usize)) as Box<dyn Rule>,
+        ),
+        map(tag("double"), |_| Box::new(Double) as Box<dyn Rule>),
+        map(
+            preceded(
+                tuple((tag("forbid"), space1)),
+                separated_list1(tuple((space0, char(','), space0)), alpha1),
+            ),
+            |forbidden: Vec<&str>| {
+                Box::new(Forbid(forbidden.into_iter().map(String::from).collect())) as Box<dyn Rule>
+            },
+        ),
+        map(tag("pair-twice"), |_| Box::new(PairTwice) as Box<dyn Rule>),
+        map(tag("sandwich"), |_| Box::new(Sandwich) as Box<dyn Rule>),
+    ))(input)
+}
+
 #[cfg(test)]
 mod test {
     use crate::day5::{
-        at_least_3_vowels, duplicate_letters, not_restricted, paired_triplet, paired_twice,
-        solve_part1, solve_part2,
+        at_least_3_vowels, classify, count_nice_from_reader, count_nicer_from_reader,
+        duplicate_letters, not_restricted, paired_triplet, paired_twice, solve_part1,
+        solve_part1_filters, solve_part1_rules, solve_part2, solve_part2_filters,
+        solve_part2_rules, Classification, RuleSet, Verdict,
     };
+    use crate::parse::ParseError;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     #[test]
     fn test_vowels() {
-        assert_eq!(true, at_least_3_vowels(&"aei"));
-        assert_eq!(true, at_least_3_vowels(&"xazegov"));
-        assert_eq!(true, at_least_3_vowels(&"aeiouaeiouaeiou"));
+        assert!(at_least_3_vowels(&"aei"));
+        assert!(at_least_3_vowels(&"xazegov"));
+        assert!(at_least_3_vowels(&"aeiouaeiouaeiou"));
     }
     #[test]
     fn test_duplicates() {
-        assert_eq!(true, duplicate_letters(&"xx"));
-        assert_eq!(true, duplicate_letters(&"abcdde"));
-        assert_eq!(true, duplicate_letters(&"aabbccdd"));
+        assert!(duplicate_letters(&"xx"));
+        assert!(duplicate_letters(&"abcdde"));
+        assert!(duplicate_letters(&"aabbccdd"));
     }
     #[test]
     fn test_restrictions() {
-        assert_eq!(false, not_restricted(&"ab"));
-        assert_eq!(false, not_restricted(&"cd"));
-        assert_eq!(false, not_restricted(&"pq"));
-        assert_eq!(false, not_restricted(&"xy"));
+        assert!(!not_restricted(&"ab"));
+        assert!(!not_restricted(&"cd"));
+        assert!(!not_restricted(&"pq"));
+        assert!(!not_restricted(&"xy"));
     }
     #[test]
     fn part1() {
@@ -101,16 +444,16 @@ mod test {
 
     #[test]
     fn duplicate_twice() {
-        assert_eq!(true, paired_twice(&"xyxy"));
-        assert_eq!(true, paired_twice(&"aabcdefgaa"));
-        assert_eq!(false, paired_twice(&"aaa"));
+        assert!(paired_twice(&"xyxy"));
+        assert!(paired_twice(&"aabcdefgaa"));
+        assert!(!paired_twice(&"aaa"));
     }
 
     #[test]
     fn separated_pair() {
-        assert_eq!(true, paired_triplet(&"xyx"));
-        assert_eq!(true, paired_triplet(&"abcdefeghi"));
-        assert_eq!(true, paired_triplet(&"aaa"));
+        assert!(paired_triplet(&"xyx"));
+        assert!(paired_triplet(&"abcdefeghi"));
+        assert!(paired_triplet(&"aaa"));
     }
     #[test]
     fn part2() {
@@ -124,4 +467,119 @@ mod test {
 
         assert_eq!(2, solve_part2(input.as_str()));
     }
+
+    #[test]
+    fn streaming() {
+        let input = [
+            "ugknbfddgicrmopn",
+            "aaa",
+            "jchzalrnumimnmhp",
+            "qjhvhtzxzqqjkmpb",
+            "xxyxx",
+            "uurcxstgmygtbstg",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            solve_part1(&input),
+            count_nice_from_reader(input.as_bytes()).unwrap()
+        );
+        assert_eq!(
+            solve_part2(&input),
+            count_nicer_from_reader(input.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn rule_sets_match_solvers() {
+        let input = [
+            "ugknbfddgicrmopn",
+            "aaa",
+            "jchzalrnumimnmhp",
+            "haegwjzuvuyypxyu",
+            "dvszwmarrgswjxmb",
+            "qjhvhtzxzqqjkmpb",
+            "xxyxx",
+            "uurcxstgmygtbstg",
+            "ieodomkazucvgmuy",
+        ]
+        .join("\n");
+        assert_eq!(solve_part1(&input), solve_part1_rules(&input));
+        assert_eq!(solve_part2(&input), solve_part2_rules(&input));
+    }
+
+    #[test]
+    fn rule_set_description() {
+        let rules = RuleSet::parse("  vowels >= 2;double ;  forbid xyz, q ").unwrap();
+        assert_eq!("vowels>=2; double; forbid xyz,q", rules.describe());
+        assert!(rules.matches("aabe"));
+        assert!(!rules.matches("aabeq"));
+        assert_eq!(
+            Some(ParseError::new(5, 1, 12, "keyword")),
+            RuleSet::parse("vowels>=3; triple").err()
+        );
+    }
+
+    #[test]
+    fn explain() {
+        let verdict = |rule: &str, passed, positions: &[usize]| Verdict {
+            rule: rule.to_string(),
+            passed,
+            positions: positions.to_vec(),
+        };
+        assert_eq!(
+            vec![
+                verdict("vowels>=3", true, &[0, 4, 5, 6]),
+                verdict("double", true, &[4]),
+                verdict("forbid ab,cd,pq,xy", false, &[0, 2, 7]),
+            ],
+            RuleSet::nice().explain("abcdeeaxy")
+        );
+        assert_eq!(
+            vec![
+                verdict("pair-twice", true, &[0, 3]),
+                verdict("sandwich", false, &[]),
+            ],
+            RuleSet::nicer().explain("xyzxy")
+        );
+    }
+
+    #[test]
+    fn fused_matches_filters() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let words: Vec<String> = (0..2000)
+            .map(|_| {
+                let length = rng.gen_range(0..20);
+                // Mostly lowercase words, with the odd capital, digit or accented letter.
+                let alphabet: Vec<char> = match rng.gen_bool(0.8) {
+                    true => "abcdepqxy".chars().collect(),
+                    false => "abeAB1 éè".chars().collect(),
+                };
+                (0..length)
+                    .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                    .collect()
+            })
+            .collect();
+        for word in &words {
+            let classification = classify(word);
+            assert_eq!(
+                Classification {
+                    three_vowels: at_least_3_vowels(&word.as_str()),
+                    double: duplicate_letters(&word.as_str()),
+                    not_restricted: not_restricted(&word.as_str()),
+                    paired_twice: paired_twice(&word.as_str()),
+                    paired_triplet: paired_triplet(&word.as_str()),
+                },
+                classification,
+                "{word}"
+            );
+        }
+        assert!(classify("éé").double);
+        assert!(classify("XYzXY").paired_twice);
+        assert!(classify("éaé").paired_triplet);
+
+        let input = words.join("\n");
+        assert_eq!(solve_part1_filters(&input), solve_part1(&input));
+        assert_eq!(solve_part2_filters(&input), solve_part2(&input));
+    }
 }
@@ -1,52 +1,370 @@
+use crate::grid::{Grid, Rect};
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::{Itertools, Product};
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{char as char_nom, line_ending, multispace1, u32 as u32_nom};
-use nom::combinator::map;
-use nom::multi::separated_list1;
-use nom::sequence::separated_pair;
+use nom::bytes::complete::{tag, take_while_m_n};
+use nom::character::complete::{char as char_nom, multispace1, u32 as u32_nom};
+use nom::combinator::{consumed, map, map_res, opt};
+use nom::sequence::{preceded, separated_pair, tuple};
 use nom::IResult;
 use std::fmt::{Display, Formatter};
-use std::ops::RangeInclusive;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::slice;
+use std::str::FromStr;
+
+/// Side of the square grid of the puzzle.
+pub const SIZE: usize = 1000;
 
 #[aoc_generator(day6)]
-fn parse_instructions(input: &str) -> Vec<(Action, Window)> {
-    instructions(input).unwrap().1
+pub fn try_parse_instructions(input: &str) -> Result<Vec<(Action, Window)>, ParseError> {
+    try_parse_instructions_in(input, SIZE, SIZE)
+}
+
+/// Parses instructions for a `width` × `height` grid, rejecting windows reaching outside of it.
+pub fn try_parse_instructions_in(
+    input: &str,
+    width: usize,
+    height: usize,
+) -> Result<Vec<(Action, Window)>, ParseError> {
+    parse::lines_checked(
+        6,
+        input,
+        consumed(instruction),
+        |(text, (action, window)): (&str, (Action, Window))| {
+            if window.from.0 > window.to.0 || window.from.1 > window.to.1 {
+                Err((
+                    text,
+                    format!("a window from its first corner to its second in `{text}`"),
+                ))
+            } else if window.to.0 >= height || window.to.1 >= width {
+                Err((
+                    text,
+                    format!("a window within {width}x{height} lights in `{text}`"),
+                ))
+            } else {
+                Ok((action, window))
+            }
+        },
+    )
 }
 
 type Point = (usize, usize);
 
-#[aoc(day6, part1, Array)]
-pub fn solve_part1(instructions: &[(Action, Window)]) -> usize {
-    let mut lights = [false; 1_000_000];
-    for (action, window) in instructions {
-        for (row, column) in window.range() {
-            let idx = row * 1000 + column;
-            lights[idx] = match action {
-                Action::Toggle => !lights[idx],
-                Action::On => true,
-                Action::Off => false,
-            };
+/// Lights of a grid of any size, stored on the heap.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LightGrid<T> {
+    lights: Grid<T>,
+}
+
+impl<T: Clone> LightGrid<T> {
+    pub fn new(width: usize, height: usize, off: T) -> Self {
+        LightGrid {
+            lights: Grid::new(width, height, off),
+        }
+    }
+}
+
+impl<T> LightGrid<T> {
+    pub fn width(&self) -> usize {
+        self.lights.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.lights.height()
+    }
+
+    pub fn contains(&self, window: &Window) -> bool {
+        window.to.0 < self.height() && window.to.1 < self.width()
+    }
+
+    /// Applies `f` to every light in `window`, ignoring the part outside the grid.
+    pub fn apply(&mut self, window: &Window, f: impl FnMut(&mut T)) {
+        if let Some(rect) = window.rect() {
+            self.lights.update(rect, f)
         }
     }
-    lights.iter().filter(|light| **light).count()
+
+    pub fn get(&self, (row, column): Point) -> Option<&T> {
+        self.lights
+            .get((i32::try_from(row).ok()?, i32::try_from(column).ok()?))
+    }
+
+    pub fn lights(&self) -> impl Iterator<Item = &T> {
+        self.lights.values()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.lights.rows()
+    }
+}
+
+#[aoc(day6, part1, Array)]
+pub fn solve_part1(instructions: &[(Action, Window)]) -> usize {
+    total(&OnOff, &run(&OnOff, instructions, SIZE, SIZE)) as usize
 }
 
 #[aoc(day6, part2)]
 pub fn solve_part2(instructions: &[(Action, Window)]) -> u32 {
-    let mut lights = [0u32; 1_000_000];
+    total(&Brightness, &run(&Brightness, instructions, SIZE, SIZE)) as u32
+}
+
+/// [solve_part1] on a coordinate-compressed grid, for grids of any size.
+#[aoc(day6, part1, Compressed)]
+pub fn solve_part1_compressed(instructions: &[(Action, Window)]) -> u64 {
+    compressed(&OnOff, instructions)
+}
+
+/// [solve_part2] on a coordinate-compressed grid, for grids of any size.
+#[aoc(day6, part2, Compressed)]
+pub fn solve_part2_compressed(instructions: &[(Action, Window)]) -> u64 {
+    compressed(&Brightness, instructions)
+}
+
+/// Applies the instructions to blocks of lights that every window either fully covers or misses,
+/// cut along the window edges, and totals the lights.
+fn compressed<M: LightModel>(model: &M, instructions: &[(Action, Window)]) -> u64 {
+    let windows = || {
+        instructions
+            .iter()
+            .filter(|(_, w)| w.from.0 <= w.to.0 && w.from.1 <= w.to.1)
+    };
+    let edges = |axis: fn(&Point) -> usize| -> Vec<u64> {
+        let mut edges: Vec<u64> = windows()
+            .flat_map(|(_, w)| [axis(&w.from) as u64, axis(&w.to) as u64 + 1])
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    };
+    let (rows, columns) = (edges(|p| p.0), edges(|p| p.1));
+    if rows.is_empty() {
+        return 0;
+    }
+    let block = |edges: &[u64], edge: usize| edges.binary_search(&(edge as u64)).unwrap() as i32;
+    let mut blocks = Grid::new(columns.len() - 1, rows.len() - 1, model.off());
+    for (action, window) in windows() {
+        let from = (block(&rows, window.from.0), block(&columns, window.from.1));
+        let to = (
+            block(&rows, window.to.0 + 1) - 1,
+            block(&columns, window.to.1 + 1) - 1,
+        );
+        blocks.update(Rect::new(from, to), |light| model.apply(light, action));
+    }
+    blocks
+        .iter()
+        .map(|((row, column), light)| {
+            let (row, column) = (row as usize, column as usize);
+            model.measure(light)
+                * (rows[row + 1] - rows[row])
+                * (columns[column + 1] - columns[column])
+        })
+        .sum()
+}
+
+/// What a light is and how instructions change it.
+pub trait LightModel {
+    type Light: Clone;
+
+    fn off(&self) -> Self::Light;
+
+    fn apply(&self, light: &mut Self::Light, action: &Action);
+
+    /// What a light adds to the total, such as whether it is on or its brightness.
+    fn measure(&self, light: &Self::Light) -> u64;
+}
+
+/// Runs `instructions` on a `width` × `height` grid of lights behaving like `model`.
+pub fn run<M: LightModel>(
+    model: &M,
+    instructions: &[(Action, Window)],
+    width: usize,
+    height: usize,
+) -> LightGrid<M::Light> {
+    let mut lights = LightGrid::new(width, height, model.off());
     for (action, window) in instructions {
-        for (row, col) in window.range() {
-            let idx = row * 1000 + col;
-            lights[idx] = match action {
-                Action::On => lights[idx] + 1,
-                Action::Off => lights[idx].saturating_sub(1),
-                Action::Toggle => lights[idx] + 2,
+        lights.apply(window, |light| model.apply(light, action));
+    }
+    lights
+}
+
+pub fn total<M: LightModel>(model: &M, lights: &LightGrid<M::Light>) -> u64 {
+    lights.lights().map(|light| model.measure(light)).sum()
+}
+
+/// Part 1, lights are either on or off. Setting a color turns a light on unless it is black.
+#[derive(Debug, Clone, Copy)]
+pub struct OnOff;
+
+/// Part 2, turning on and off adds and removes one brightness and toggling adds two. Turning on
+/// at a level sets the brightness, setting a color sets it to the brightest channel.
+#[derive(Debug, Clone, Copy)]
+pub struct Brightness;
+
+/// [Brightness] that never exceeds the given maximum.
+#[derive(Debug, Clone, Copy)]
+pub struct BoundedBrightness(pub u32);
+
+/// Colored lights, turning on makes them white, or gray at a level, and toggling inverts them.
+/// Counts the lights that are not black.
+#[derive(Debug, Clone, Copy)]
+pub struct Color;
+
+/// Counts how often each light was toggled.
+#[derive(Debug, Clone, Copy)]
+pub struct ToggleCounter;
+
+impl LightModel for OnOff {
+    type Light = bool;
+
+    fn off(&self) -> bool {
+        false
+    }
+
+    fn apply(&self, light: &mut bool, action: &Action) {
+        *light = match action {
+            Action::Toggle => !*light,
+            Action::On | Action::OnAt(_) => true,
+            Action::Off => false,
+            Action::Set(color) => *color != [0; 3],
+        }
+    }
+
+    fn measure(&self, &light: &bool) -> u64 {
+        light as u64
+    }
+}
+
+impl LightModel for Brightness {
+    type Light = u32;
+
+    fn off(&self) -> u32 {
+        0
+    }
+
+    fn apply(&self, light: &mut u32, action: &Action) {
+        *light = match action {
+            Action::On => light.saturating_add(1),
+            Action::Off => light.saturating_sub(1),
+            Action::Toggle => light.saturating_add(2),
+            Action::OnAt(level) => *level,
+            Action::Set(color) => *color.iter().max().unwrap() as u32,
+        }
+    }
+
+    fn measure(&self, &light: &u32) -> u64 {
+        light as u64
+    }
+}
+
+impl LightModel for BoundedBrightness {
+    type Light = u32;
+
+    fn off(&self) -> u32 {
+        0
+    }
+
+    fn apply(&self, light: &mut u32, action: &Action) {
+        Brightness.apply(light, action);
+        *light = (*light).min(self.0);
+    }
+
+    fn measure(&self, &light: &u32) -> u64 {
+        light as u64
+    }
+}
+
+impl LightModel for Color {
+    type Light = Rgb;
+
+    fn off(&self) -> Rgb {
+        [0; 3]
+    }
+
+    fn apply(&self, light: &mut Rgb, action: &Action) {
+        *light = match action {
+            Action::On => [255; 3],
+            Action::OnAt(level) => [(*level).min(255) as u8; 3],
+            Action::Off => [0; 3],
+            Action::Toggle => light.map(|channel| 255 - channel),
+            Action::Set(color) => *color,
+        }
+    }
+
+    fn measure(&self, light: &Rgb) -> u64 {
+        (*light != [0; 3]) as u64
+    }
+}
+
+impl LightModel for ToggleCounter {
+    type Light = u32;
+
+    fn off(&self) -> u32 {
+        0
+    }
+
+    fn apply(&self, light: &mut u32, action: &Action) {
+        if *action == Action::Toggle {
+            *light = light.saturating_add(1);
+        }
+    }
+
+    fn measure(&self, &light: &u32) -> u64 {
+        light as u64
+    }
+}
+
+/// A [LightModel] picked at runtime, named as in [Model::from_str].
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Model {
+    OnOff,
+    Brightness,
+    BoundedBrightness(u32),
+    Color,
+    ToggleCounter,
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    /// One of `on-off`, `brightness`, `brightness<=N`, `color` or `toggles`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "on-off" => Ok(Model::OnOff),
+            "brightness" => Ok(Model::Brightness),
+            "color" => Ok(Model::Color),
+            "toggles" => Ok(Model::ToggleCounter),
+            _ => name
+                .strip_prefix("brightness<=")
+                .and_then(|max| max.parse().ok())
+                .map(Model::BoundedBrightness)
+                .ok_or_else(|| format!("unknown light model `{name}`")),
+        }
+    }
+}
+
+impl Model {
+    /// Total of the lights of a `width` × `height` grid after running `instructions`.
+    pub fn total(&self, instructions: &[(Action, Window)], width: usize, height: usize) -> u64 {
+        fn total_of<M: LightModel>(
+            model: &M,
+            instructions: &[(Action, Window)],
+            width: usize,
+            height: usize,
+        ) -> u64 {
+            total(model, &run(model, instructions, width, height))
+        }
+        match *self {
+            Model::OnOff => total_of(&OnOff, instructions, width, height),
+            Model::Brightness => total_of(&Brightness, instructions, width, height),
+            Model::BoundedBrightness(max) => {
+                total_of(&BoundedBrightness(max), instructions, width, height)
             }
+            Model::Color => total_of(&Color, instructions, width, height),
+            Model::ToggleCounter => total_of(&ToggleCounter, instructions, width, height),
         }
     }
-    lights.iter().sum::<u32>()
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -65,36 +383,145 @@ impl Display for Window {
 }
 
 impl Window {
-    fn range(&self) -> Product<RangeInclusive<usize>, RangeInclusive<usize>> {
-        (self.from.0..=(self.to.0)).cartesian_product(self.from.1..=(self.to.1))
+    /// The window as grid coordinates, `None` when it starts beyond any grid. A far corner
+    /// beyond one is pulled in to `i32::MAX`, which still lies outside every grid.
+    fn rect(&self) -> Option<Rect> {
+        let far = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+        Some(Rect::new(
+            (
+                i32::try_from(self.from.0).ok()?,
+                i32::try_from(self.from.1).ok()?,
+            ),
+            (far(self.to.0), far(self.to.1)),
+        ))
+    }
+
+    fn covers(&self, other: &Window) -> bool {
+        self.from.0 <= other.from.0
+            && self.from.1 <= other.from.1
+            && other.to.0 <= self.to.0
+            && other.to.1 <= self.to.1
+    }
+
+    /// The window covering exactly both windows, when they share a whole side.
+    fn merge(&self, other: &Window) -> Option<Window> {
+        let (first, second) = if (self.from, self.to) <= (other.from, other.to) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let same_rows = first.from.0 == second.from.0 && first.to.0 == second.to.0;
+        let same_columns = first.from.1 == second.from.1 && first.to.1 == second.to.1;
+        ((same_rows && first.to.1 + 1 == second.from.1)
+            || (same_columns && first.to.0 + 1 == second.from.0))
+            .then_some(Window {
+                from: first.from,
+                to: second.to,
+            })
+    }
+}
+
+/// Instructions in the input syntax, one per line, parsing back to the same instructions.
+pub fn format_instructions(instructions: &[(Action, Window)]) -> String {
+    instructions
+        .iter()
+        .map(|(action, window)| match action {
+            Action::OnAt(level) => format!("{action} {window} at {level}"),
+            _ => format!("{action} {window}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Equivalent, shorter instructions for models where turning lights on or off does not depend
+/// on their previous state, such as [OnOff] and [Color] but not [Brightness].
+///
+/// Drops instructions whose window a later `turn on` or `turn off` covers, and merges consecutive
+/// identical actions on windows sharing a side.
+pub fn optimize(instructions: &[(Action, Window)]) -> Vec<(Action, Window)> {
+    let mut instructions = instructions.to_vec();
+    loop {
+        let length = instructions.len();
+        instructions = merge_adjacent(&remove_overwritten(&instructions));
+        if instructions.len() == length {
+            return instructions;
+        }
     }
 }
 
+fn remove_overwritten(instructions: &[(Action, Window)]) -> Vec<(Action, Window)> {
+    instructions
+        .iter()
+        .enumerate()
+        .filter(|(idx, (_, window))| {
+            !instructions[idx + 1..].iter().any(|(action, later)| {
+                matches!(action, Action::On | Action::Off) && later.covers(window)
+            })
+        })
+        .map(|(_, instruction)| instruction.clone())
+        .collect()
+}
+
+fn merge_adjacent(instructions: &[(Action, Window)]) -> Vec<(Action, Window)> {
+    let mut merged: Vec<(Action, Window)> = Vec::with_capacity(instructions.len());
+    for (action, window) in instructions {
+        match merged.last_mut() {
+            Some((last_action, last)) if last_action == action => {
+                if let Some(window) = last.merge(window) {
+                    *last = window;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        merged.push((action.clone(), window.clone()));
+    }
+    merged
+}
+
+pub type Rgb = [u8; 3];
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Action {
     Toggle,
     On,
     Off,
+    /// `turn on .. at n`
+    OnAt(u32),
+    /// `set color #rrggbb ..`
+    Set(Rgb),
 }
 
+/// The keyword written before the window, so `OnAt(n)` shows as plain `turn on` because its level
+/// follows the window. This alone does not round-trip; format whole instructions with
+/// [format_instructions] to parse them back.
 impl Display for Action {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Action::Toggle => write!(f, "toggle"),
-            Action::On => write!(f, "turn on"),
+            Action::On | Action::OnAt(_) => write!(f, "turn on"),
             Action::Off => write!(f, "turn off"),
+            Action::Set([r, g, b]) => write!(f, "set color #{r:02x}{g:02x}{b:02x}"),
         }
     }
 }
 
-fn instructions(input: &str) -> IResult<&str, Vec<(Action, Window)>> {
-    separated_list1(line_ending, instruction)(input)
-}
 fn instruction(input: &str) -> IResult<&str, (Action, Window)> {
-    separated_pair(action, multispace1, window)(input)
+    alt((
+        map(
+            tuple((
+                turn_on,
+                multispace1,
+                window,
+                opt(preceded(tag(" at "), u32_nom)),
+            )),
+            |(on, _, window, level)| (level.map_or(on, Action::OnAt), window),
+        ),
+        separated_pair(action, multispace1, window),
+    ))(input)
 }
 fn action(input: &str) -> IResult<&str, Action> {
-    alt((toggle, turn_on, turn_off))(input)
+    alt((toggle, turn_on, turn_off, set_color))(input)
 }
 
 fn toggle(input: &str) -> IResult<&str, Action> {
@@ -106,6 +533,18 @@ fn turn_on(input: &str) -> IResult<&str, Action> {
 fn turn_off(input: &str) -> IResult<&str, Action> {
     map(tag("turn off"), |_| Action::Off)(input)
 }
+fn set_color(input: &str) -> IResult<&str, Action> {
+    let channel = || {
+        map_res(
+            take_while_m_n(2, 2, |c: char| c.is_ascii_hexdigit()),
+            |hex| u8::from_str_radix(hex, 16),
+        )
+    };
+    map(
+        preceded(tag("set color #"), tuple((channel(), channel(), channel()))),
+        |(r, g, b)| Action::Set([r, g, b]),
+    )(input)
+}
 
 fn point(input: &str) -> IResult<&str, (usize, usize)> {
     map(separated_pair(u32_nom, char_nom(','), u32_nom), |(r, c)| {
@@ -119,10 +558,139 @@ fn window(input: &str) -> IResult<&str, Window> {
     )(input)
 }
 
+/// Grayscale frames of a light show, one per instruction, captured as the show is replayed so
+/// only the current frame is ever held in memory.
+pub struct Animation<'a, M: LightModel, S> {
+    pub width: usize,
+    pub height: usize,
+    model: &'a M,
+    instructions: slice::Iter<'a, (Action, Window)>,
+    lights: LightGrid<M::Light>,
+    scale: usize,
+    shade: S,
+}
+
+impl<'a, M: LightModel, S: Fn(&M::Light) -> u8> Animation<'a, M, S> {
+    /// Replays `instructions` on a `width` × `height` grid of `model` lights, capturing a frame
+    /// after every instruction. Frames are shrunk by averaging square blocks of lights so that no
+    /// side exceeds `max_side` pixels, and `shade` gives the gray level of a light.
+    pub fn record(
+        model: &'a M,
+        instructions: &'a [(Action, Window)],
+        (width, height): (usize, usize),
+        max_side: usize,
+        shade: S,
+    ) -> Self {
+        let scale = width.max(height).div_ceil(max_side.max(1)).max(1);
+        Animation {
+            width: width.div_ceil(scale),
+            height: height.div_ceil(scale),
+            model,
+            instructions: instructions.iter(),
+            lights: LightGrid::new(width, height, model.off()),
+            scale,
+            shade,
+        }
+    }
+
+    /// Writes every remaining frame to `dir` as `frame0000.pgm`, `frame0001.pgm` and so on.
+    pub fn write_pgm(self, dir: impl AsRef<Path>) -> io::Result<()> {
+        fs::create_dir_all(&dir)?;
+        let size = (self.width, self.height);
+        for (idx, frame) in self.enumerate() {
+            let path = dir.as_ref().join(format!("frame{idx:04}.pgm"));
+            write_pgm_frame(BufWriter::new(File::create(path)?), size, &frame)?;
+        }
+        Ok(())
+    }
+
+    /// Streams every remaining frame to `out` as monochrome YUV4MPEG2 video, playable by most
+    /// video tools.
+    pub fn write_y4m(self, fps: u32, mut out: impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "YUV4MPEG2 W{} H{} F{fps}:1 Ip A1:1 Cmono",
+            self.width, self.height
+        )?;
+        for frame in self {
+            out.write_all(b"FRAME\n")?;
+            out.write_all(&frame)?;
+        }
+        out.flush()
+    }
+}
+
+impl<M: LightModel, S: Fn(&M::Light) -> u8> Iterator for Animation<'_, M, S> {
+    /// Row-major pixels of the frame after the next instruction.
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        let (action, window) = self.instructions.next()?;
+        let model = self.model;
+        self.lights
+            .apply(window, |light| model.apply(light, action));
+        Some(downsample(&self.lights, self.scale, &self.shade))
+    }
+}
+
+/// Writes `pixels` as a binary (`P5`) PGM image of `width` × `height`.
+pub fn write_pgm_frame(
+    mut out: impl Write,
+    (width, height): (usize, usize),
+    pixels: &[u8],
+) -> io::Result<()> {
+    write!(out, "P5\n{width} {height}\n255\n")?;
+    out.write_all(pixels)?;
+    out.flush()
+}
+
+/// Average shade of every `scale` × `scale` block of lights.
+fn downsample<T>(lights: &LightGrid<T>, scale: usize, shade: impl Fn(&T) -> u8) -> Vec<u8> {
+    let (width, height) = (
+        lights.width().div_ceil(scale),
+        lights.height().div_ceil(scale),
+    );
+    let mut sums = vec![(0u64, 0u64); width * height];
+    for (row, lights_row) in lights.rows().enumerate() {
+        for (column, light) in lights_row.iter().enumerate() {
+            let sum = &mut sums[row / scale * width + column / scale];
+            sum.0 += shade(light) as u64;
+            sum.1 += 1;
+        }
+    }
+    sums.into_iter()
+        .map(|(total, count)| (total / count.max(1)) as u8)
+        .collect()
+}
+
+/// Binary (`P6`) PPM heatmap of brightness, from black through red and yellow to white for the
+/// brightest light.
+pub fn heatmap(lights: &LightGrid<u32>) -> Vec<u8> {
+    let brightest = lights.lights().copied().max().unwrap_or(0).max(1);
+    let mut ppm = format!("P6\n{} {}\n255\n", lights.width(), lights.height()).into_bytes();
+    for &light in lights.lights() {
+        let heat = (light as u64 * 765 / brightest as u64) as u32;
+        ppm.extend_from_slice(&[
+            heat.min(255) as u8,
+            heat.saturating_sub(255).min(255) as u8,
+            heat.saturating_sub(510).min(255) as u8,
+        ]);
+    }
+    ppm
+}
+
 #[cfg(test)]
 mod test {
     use crate::day6::Action::On;
-    use crate::day6::{instruction, solve_part1, solve_part2, Window};
+    use crate::day6::{
+        format_instructions, heatmap, instruction, optimize, run, solve_part1,
+        solve_part1_compressed, solve_part2, solve_part2_compressed, try_parse_instructions,
+        try_parse_instructions_in, write_pgm_frame, Action, Animation, Brightness, Color,
+        LightGrid, Model, OnOff, Window,
+    };
+    use crate::parse::ParseError;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     #[test]
     fn parse_instruction() {
@@ -161,4 +729,273 @@ mod test {
 
         assert_eq!(2000001, solve_part2(&instructions));
     }
+
+    #[test]
+    fn compressed_matches_grid() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let instructions: Vec<(Action, Window)> = (0..200)
+            .map(|_| {
+                let action = [Action::On, Action::Off, Action::Toggle][rng.gen_range(0..3)].clone();
+                let (r0, r1) = (rng.gen_range(0..1000), rng.gen_range(0..1000));
+                let (c0, c1) = (rng.gen_range(0..1000), rng.gen_range(0..1000));
+                let window = Window {
+                    from: (r0.min(r1), c0.min(c1)),
+                    to: (r0.max(r1), c0.max(c1)),
+                };
+                (action, window)
+            })
+            .collect();
+        assert_eq!(
+            solve_part1(&instructions) as u64,
+            solve_part1_compressed(&instructions)
+        );
+        assert_eq!(
+            solve_part2(&instructions) as u64,
+            solve_part2_compressed(&instructions)
+        );
+        assert_eq!(0, solve_part1_compressed(&[]));
+    }
+
+    #[test]
+    fn compressed_huge_grid() {
+        let instructions = [
+            instruction("turn on 0,0 through 999999,999999").unwrap().1,
+            instruction("toggle 0,0 through 999999,0").unwrap().1,
+            instruction("turn off 499999,499999 through 500000,500000")
+                .unwrap()
+                .1,
+        ];
+        assert_eq!(
+            1_000_000_000_000 - 1_000_000 - 4,
+            solve_part1_compressed(&instructions)
+        );
+        assert_eq!(
+            1_000_000_000_000 + 2 * 1_000_000 - 4,
+            solve_part2_compressed(&instructions)
+        );
+    }
+
+    #[test]
+    fn window_bounds() {
+        assert_eq!(
+            Some(ParseError::new(
+                6,
+                2,
+                1,
+                "a window within 1000x1000 lights in `turn on 0,0 through 1000,5`"
+            )),
+            try_parse_instructions("toggle 0,0 through 999,999\nturn on 0,0 through 1000,5").err()
+        );
+        assert_eq!(
+            Some(ParseError::new(
+                6,
+                1,
+                1,
+                "a window from its first corner to its second in `turn off 5,5 through 4,9`"
+            )),
+            try_parse_instructions("turn off 5,5 through 4,9").err()
+        );
+
+        let instructions = try_parse_instructions_in("turn on 1,2 through 2,4", 5, 3).unwrap();
+        let mut lights = LightGrid::new(5, 3, false);
+        assert!(lights.contains(&instructions[0].1));
+        lights.apply(&instructions[0].1, |light| *light = true);
+        assert_eq!(6, lights.lights().filter(|light| **light).count());
+        assert_eq!(Some(&true), lights.get((2, 4)));
+        assert!(try_parse_instructions_in("turn on 1,2 through 3,4", 5, 3).is_err());
+
+        // Coordinates past i32 must not wrap around onto lights inside the grid.
+        assert_eq!(Some(&false), lights.get((0, 0)));
+        assert_eq!(None, lights.get(((1 << 32) + 2, 4)));
+        assert_eq!(None, lights.get((2, (1 << 32) + 4)));
+        let beyond = Window {
+            from: (1 << 32, 0),
+            to: ((1 << 32) + 2, 4),
+        };
+        lights.apply(&beyond, |light| *light = !*light);
+        let reaching = Window {
+            from: (0, 0),
+            to: (0, 1 << 32),
+        };
+        lights.apply(&reaching, |light| *light = true);
+        assert_eq!(6 + 5, lights.lights().filter(|light| **light).count());
+    }
+
+    #[test]
+    fn extended_instructions() {
+        let instructions = try_parse_instructions(
+            "turn on 0,0 through 9,9 at 5\nset color #ff8000 0,0 through 4,9\ntoggle 0,0 through 0,9",
+        )
+        .unwrap();
+        assert_eq!(Action::OnAt(5), instructions[0].0);
+        assert_eq!(Action::Set([255, 128, 0]), instructions[1].0);
+        assert!(try_parse_instructions("set color #ff80 0,0 through 4,9").is_err());
+        assert!(try_parse_instructions("toggle 0,0 through 4,9 at 3").is_err());
+
+        let total = |model: &str| model.parse::<Model>().unwrap().total(&instructions, 10, 10);
+        assert_eq!(90, total("on-off"));
+        assert_eq!(40 * 255 + 10 * 257 + 50 * 5, total("brightness"));
+        assert_eq!(50 * 6 + 50 * 5, total("brightness<=6"));
+        assert_eq!(100, total("color"));
+        assert_eq!(10, total("toggles"));
+        assert!("sparkles".parse::<Model>().is_err());
+    }
+
+    #[test]
+    fn brightness_saturates() {
+        let instructions = try_parse_instructions(
+            "turn on 0,0 through 0,0 at 4294967295\nturn on 0,0 through 0,0\ntoggle 0,0 through 0,0",
+        )
+        .unwrap();
+        let total = |model: &str| model.parse::<Model>().unwrap().total(&instructions, 1, 1);
+        assert_eq!(u32::MAX as u64, total("brightness"));
+        assert_eq!(7, total("brightness<=7"));
+    }
+
+    #[test]
+    fn animation() {
+        let instructions = try_parse_instructions_in(
+            "turn on 0,0 through 1,1\ntoggle 0,0 through 3,0\nturn off 0,0 through 4,4",
+            5,
+            5,
+        )
+        .unwrap();
+        let shade = |&on: &bool| if on { 255 } else { 0 };
+        let record = || Animation::record(&OnOff, &instructions, (5, 5), 3, shade);
+        let animation = record();
+        assert_eq!((3, 3), (animation.width, animation.height));
+        let frames: Vec<Vec<u8>> = animation.collect();
+        assert_eq!(
+            vec![
+                vec![255, 0, 0, 0, 0, 0, 0, 0, 0],
+                vec![127, 0, 0, 127, 0, 0, 0, 0, 0],
+                vec![0; 9],
+            ],
+            frames
+        );
+
+        let mut pgm = vec![];
+        write_pgm_frame(&mut pgm, (3, 3), &frames[1]).unwrap();
+        assert_eq!([b"P5\n3 3\n255\n".as_slice(), &frames[1]].concat(), pgm);
+
+        let mut y4m = vec![];
+        record().write_y4m(10, &mut y4m).unwrap();
+        let header = b"YUV4MPEG2 W3 H3 F10:1 Ip A1:1 Cmono\n";
+        assert!(y4m.starts_with(header));
+        assert_eq!(header.len() + 3 * (6 + 9), y4m.len());
+        assert!(y4m.ends_with(&[b"FRAME\n".as_slice(), &frames[2]].concat()));
+
+        let dir = std::env::temp_dir().join(format!("day6-frames-{}", std::process::id()));
+        record().write_pgm(&dir).unwrap();
+        let mut last = vec![];
+        write_pgm_frame(&mut last, (3, 3), &frames[2]).unwrap();
+        assert_eq!(last, std::fs::read(dir.join("frame0002.pgm")).unwrap());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn brightness_heatmap() {
+        let instructions =
+            try_parse_instructions_in("toggle 0,0 through 0,1\nturn on 0,0 through 0,0", 3, 1)
+                .unwrap();
+        let lights = run(&Brightness, &instructions, 3, 1);
+        assert_eq!(
+            [
+                b"P6\n3 1\n255\n".as_slice(),
+                &[255, 255, 255, 255, 255, 0, 0, 0, 0]
+            ]
+            .concat(),
+            heatmap(&lights)
+        );
+    }
+
+    fn random_instructions(seed: u64, count: usize, size: usize) -> Vec<(Action, Window)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| {
+                let action = match rng.gen_range(0..5) {
+                    0 => Action::On,
+                    1 => Action::Off,
+                    2 => Action::Toggle,
+                    3 => Action::OnAt(rng.gen_range(0..10)),
+                    _ => Action::Set(rng.gen()),
+                };
+                let (r0, r1) = (rng.gen_range(0..size), rng.gen_range(0..size));
+                let (c0, c1) = (rng.gen_range(0..size), rng.gen_range(0..size));
+                let window = Window {
+                    from: (r0.min(r1), c0.min(c1)),
+                    to: (r0.max(r1), c0.max(c1)),
+                };
+                (action, window)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let instructions = random_instructions(24, 100, 1000);
+        let text = format_instructions(&instructions);
+        assert_eq!(Ok(instructions), try_parse_instructions(&text));
+        assert_eq!("turn on", Action::OnAt(7).to_string());
+        assert_eq!(
+            "turn on 1,2 through 3,4 at 7\nset color #00ff0a 0,0 through 0,0",
+            format_instructions(&[
+                (
+                    Action::OnAt(7),
+                    Window {
+                        from: (1, 2),
+                        to: (3, 4)
+                    }
+                ),
+                (
+                    Action::Set([0, 255, 10]),
+                    Window {
+                        from: (0, 0),
+                        to: (0, 0)
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn optimizer() {
+        let instructions = try_parse_instructions_in(
+            "toggle 0,0 through 2,2\n\
+             turn on 0,0 through 4,1\n\
+             turn on 0,2 through 4,4\n\
+             turn off 1,1 through 1,1\n\
+             turn off 1,2 through 1,2\n\
+             toggle 0,0 through 0,0",
+            5,
+            5,
+        )
+        .unwrap();
+        let optimized = optimize(&instructions);
+        assert_eq!(
+            "turn on 0,0 through 4,4\nturn off 1,1 through 1,2\ntoggle 0,0 through 0,0",
+            format_instructions(&optimized)
+        );
+        assert_eq!(
+            run(&OnOff, &instructions, 5, 5),
+            run(&OnOff, &optimized, 5, 5)
+        );
+    }
+
+    #[test]
+    fn optimizer_equivalence() {
+        for seed in 0..50 {
+            let instructions = random_instructions(seed, 40, 12);
+            let optimized = optimize(&instructions);
+            assert!(optimized.len() <= instructions.len());
+            assert_eq!(
+                run(&OnOff, &instructions, 12, 12),
+                run(&OnOff, &optimized, 12, 12)
+            );
+            assert_eq!(
+                run(&Color, &instructions, 12, 12),
+                run(&Color, &optimized, 12, 12)
+            );
+        }
+    }
 }
@@ -1,85 +1,186 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, line_ending, u16 as u16_nom};
+use nom::character::complete::{alpha1, u16 as u16_nom};
 use nom::combinator::{map, verify};
-use nom::multi::separated_list1;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 
 #[aoc_generator(day7)]
-fn parse_instructions(input: &str) -> Vec<Assignment> {
-    assignments(input).unwrap().1
+pub fn try_parse_instructions(input: &str) -> Result<Vec<Assignment>, ParseError> {
+    parse::lines(7, input, assignment)
 }
 
 #[aoc(day7, part1)]
-pub fn solve_part1(assignments: &[Assignment]) -> u16 {
-    let computer = Computer {
-        definitions: HashMap::from_iter(
-            assignments
-                .iter()
-                .map(|(expression, assignment)| (assignment.clone(), expression.clone())),
-        ),
-    };
-    let mut registers: HashMap<String, u16> = HashMap::new();
-    let a = "a".to_string();
-    computer.eval(&a, &mut registers)
+pub fn solve_part1(assignments: &[Assignment]) -> Result<u16, CircuitError> {
+    signal(assignments, "a")
 }
 
 #[aoc(day7, part2)]
-pub fn solve_part2(assignments: &[Assignment]) -> u16 {
-    let computer = Computer {
-        definitions: HashMap::from_iter(
-            assignments
-                .iter()
-                .map(|(expression, assignment)| (assignment.clone(), expression.clone())),
-        ),
-    };
-    let mut registers: HashMap<String, u16> = HashMap::new();
-    let a = "a".to_string();
-    let b_value = computer.eval(&a, &mut registers);
-    registers.clear();
-    let b = "b".to_string();
-    let computer2 = Computer {
-        definitions: HashMap::from_iter(assignments.iter().map(|(expression, assignment)| {
-            if *assignment == b {
-                (assignment.clone(), Expression::Value(b_value))
-            } else {
-                (assignment.clone(), expression.clone())
+pub fn solve_part2(assignments: &[Assignment]) -> Result<u16, CircuitError> {
+    let a = signal(assignments, "a")?;
+    let overridden: Vec<Assignment> = assignments
+        .iter()
+        .map(|(expression, wire)| match wire.as_str() {
+            "b" => (Expression::Value(a), wire.clone()),
+            _ => (expression.clone(), wire.clone()),
+        })
+        .collect();
+    signal(&overridden, "a")
+}
+
+fn signal(assignments: &[Assignment], wire: &str) -> Result<u16, CircuitError> {
+    evaluate(assignments)?
+        .get(wire)
+        .copied()
+        .ok_or_else(|| CircuitError::Missing {
+            wire: wire.to_string(),
+        })
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum CircuitError {
+    /// `wire` feeds into `used_by` but nothing provides it a signal.
+    Undefined { wire: String, used_by: String },
+    /// `wire` is given a signal more than once.
+    Duplicate { wire: String },
+    /// The circuit has no `wire` to read the answer from.
+    Missing { wire: String },
+    /// Wires feeding into each other, each one into the next and the last into the first.
+    Cycle(Vec<String>),
+}
+
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitError::Undefined { wire, used_by } => {
+                write!(f, "wire `{wire}` used by `{used_by}` has no signal")
             }
-        })),
-    };
-    computer2.eval(&a, &mut registers)
+            CircuitError::Duplicate { wire } => {
+                write!(f, "wire `{wire}` is given a signal more than once")
+            }
+            CircuitError::Missing { wire } => write!(f, "circuit has no wire `{wire}`"),
+            CircuitError::Cycle(wires) => {
+                write!(
+                    f,
+                    "cycle through wires {} -> {}",
+                    wires.join(" -> "),
+                    wires[0]
+                )
+            }
+        }
+    }
 }
 
-#[derive(Debug)]
-struct Computer {
-    definitions: HashMap<String, Expression>,
+impl Error for CircuitError {}
+
+/// Signals of every wire, evaluated in dependency order (Kahn's algorithm) without recursion.
+pub fn evaluate(assignments: &[Assignment]) -> Result<HashMap<String, u16>, CircuitError> {
+    let mut definitions: HashMap<&str, &Expression> = HashMap::with_capacity(assignments.len());
+    for (expression, wire) in assignments {
+        if definitions.insert(wire, expression).is_some() {
+            return Err(CircuitError::Duplicate { wire: wire.clone() });
+        }
+    }
+
+    let mut pending: HashMap<&str, usize> = HashMap::new();
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (expression, wire) in assignments {
+        let inputs = expression.inputs();
+        for &input in &inputs {
+            if !definitions.contains_key(input) {
+                return Err(CircuitError::Undefined {
+                    wire: input.to_string(),
+                    used_by: wire.clone(),
+                });
+            }
+            dependents.entry(input).or_default().push(wire);
+        }
+        pending.insert(wire, inputs.len());
+    }
+
+    let mut ready: VecDeque<&str> = assignments
+        .iter()
+        .map(|(_, wire)| wire.as_str())
+        .filter(|wire| pending[wire] == 0)
+        .collect();
+    let mut signals: HashMap<String, u16> = HashMap::with_capacity(definitions.len());
+    while let Some(wire) = ready.pop_front() {
+        let signal = definitions[wire].eval(|input| signals[input]);
+        signals.insert(wire.to_string(), signal);
+        for &dependent in dependents.get(wire).into_iter().flatten() {
+            let count = pending.get_mut(dependent).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.push_back(dependent);
+            }
+        }
+    }
+
+    if signals.len() < definitions.len() {
+        return Err(CircuitError::Cycle(cycle(&definitions, &signals)));
+    }
+    Ok(signals)
 }
 
-impl Computer {
-    fn eval(&self, register: &String, registers: &mut HashMap<String, u16>) -> u16 {
-        if let Some(&value) = registers.get(register) {
-            value
-        } else {
-            let expression = self.definitions.get(register).unwrap();
-            let value = match expression {
-                Expression::Value(val) => *val,
-                Expression::Not(reg) => !self.eval(reg, registers),
-                Expression::And(left, right) => {
-                    self.eval(left, registers) & self.eval(right, registers)
-                }
-                Expression::ValueAnd(val, reg) => val & self.eval(reg, registers),
-                Expression::Or(left, right) => {
-                    self.eval(left, registers) | self.eval(right, registers)
-                }
-                Expression::Lshift(reg, val) => self.eval(reg, registers) << *val,
-                Expression::Rshift(reg, val) => self.eval(reg, registers) >> *val,
-                Expression::Register(reg) => self.eval(reg, registers),
-            };
-            registers.insert(register.clone(), value);
-            value
+/// Some cycle among the wires left without a signal, every one of which has an input without a
+/// signal, so following those inputs eventually comes back around.
+fn cycle(definitions: &HashMap<&str, &Expression>, signals: &HashMap<String, u16>) -> Vec<String> {
+    let unresolved = |wire: &&str| !signals.contains_key(*wire);
+    let mut wire = definitions
+        .keys()
+        .copied()
+        .filter(unresolved)
+        .min()
+        .unwrap();
+    let mut chain: Vec<&str> = vec![];
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    while !seen.contains_key(wire) {
+        seen.insert(wire, chain.len());
+        chain.push(wire);
+        wire = definitions[wire]
+            .inputs()
+            .into_iter()
+            .filter(unresolved)
+            .min()
+            .unwrap();
+    }
+    // Listed in the direction signals flow, each wire feeding into the next, from the first name.
+    let mut cycle: Vec<String> = chain[seen[wire]..].iter().map(|w| w.to_string()).collect();
+    cycle.reverse();
+    let first = cycle.iter().enumerate().min_by_key(|(_, w)| *w).unwrap().0;
+    cycle.rotate_left(first);
+    cycle
+}
+
+impl Expression {
+    /// Wires this expression reads from.
+    fn inputs(&self) -> Vec<&str> {
+        match self {
+            Expression::Value(_) => vec![],
+            Expression::And(left, right) | Expression::Or(left, right) => vec![left, right],
+            Expression::Not(wire)
+            | Expression::ValueAnd(_, wire)
+            | Expression::Lshift(wire, _)
+            | Expression::Rshift(wire, _)
+            | Expression::Register(wire) => vec![wire],
+        }
+    }
+
+    fn eval(&self, signal: impl Fn(&str) -> u16) -> u16 {
+        match self {
+            Expression::Value(val) => *val,
+            Expression::Not(reg) => !signal(reg),
+            Expression::And(left, right) => signal(left) & signal(right),
+            Expression::ValueAnd(val, reg) => val & signal(reg),
+            Expression::Or(left, right) => signal(left) | signal(right),
+            Expression::Lshift(reg, val) => signal(reg).checked_shl(*val as u32).unwrap_or(0),
+            Expression::Rshift(reg, val) => signal(reg).checked_shr(*val as u32).unwrap_or(0),
+            Expression::Register(reg) => signal(reg),
         }
     }
 }
@@ -98,15 +199,6 @@ pub enum Expression {
 
 pub type Assignment = (Expression, String);
 
-#[derive(Debug, Eq, PartialEq, Hash)]
-struct Register {
-    name: String,
-}
-
-fn assignments(input: &str) -> IResult<&str, Vec<Assignment>> {
-    separated_list1(line_ending, assignment)(input)
-}
-
 fn assignment(input: &str) -> IResult<&str, Assignment> {
     separated_pair(expression, tag(" -> "), register)(input)
 }
@@ -178,7 +270,10 @@ fn register_expression(input: &str) -> IResult<&str, Expression> {
 
 #[cfg(test)]
 mod test {
-    use crate::day7::{expression, Expression};
+    use crate::day7::{
+        evaluate, expression, solve_part1, try_parse_instructions, CircuitError, Expression,
+    };
+    use std::collections::HashMap;
 
     #[test]
     fn parse_expressions() {
@@ -191,4 +286,107 @@ mod test {
             expression("1 AND cx")
         );
     }
+
+    #[test]
+    fn example_circuit() {
+        let circuit = try_parse_instructions(
+            "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\n\
+             y RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i",
+        )
+        .unwrap();
+        let expected: HashMap<String, u16> = [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ]
+        .into_iter()
+        .map(|(wire, signal)| (wire.to_string(), signal))
+        .collect();
+        assert_eq!(Ok(expected), evaluate(&circuit));
+    }
+
+    #[test]
+    fn wide_shifts() {
+        let circuit = try_parse_instructions("7 -> b\nb LSHIFT 16 -> c\nb RSHIFT 20 -> d").unwrap();
+        let signals = evaluate(&circuit).unwrap();
+        assert_eq!((0, 0), (signals["c"], signals["d"]));
+        let circuit = try_parse_instructions("7 -> b\nb LSHIFT 16 -> a").unwrap();
+        assert_eq!(Ok(0), solve_part1(&circuit));
+    }
+
+    #[test]
+    fn missing_output() {
+        let circuit = try_parse_instructions("7 -> b").unwrap();
+        assert_eq!(
+            Err(CircuitError::Missing {
+                wire: "a".to_string()
+            }),
+            solve_part1(&circuit)
+        );
+    }
+
+    #[test]
+    fn undefined_wire() {
+        let circuit = try_parse_instructions("1 -> a\na AND zz -> b").unwrap();
+        assert_eq!(
+            Err(CircuitError::Undefined {
+                wire: "zz".to_string(),
+                used_by: "b".to_string()
+            }),
+            evaluate(&circuit)
+        );
+    }
+
+    #[test]
+    fn duplicate_wire() {
+        let circuit = try_parse_instructions("1 -> a\nb -> c\n2 -> b\n3 -> c\nc -> d").unwrap();
+        assert_eq!(
+            Err(CircuitError::Duplicate {
+                wire: "c".to_string()
+            }),
+            evaluate(&circuit)
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let circuit =
+            try_parse_instructions("1 -> a\na OR d -> b\nb -> c\nNOT c -> d\nd -> e").unwrap();
+        let error = evaluate(&circuit).unwrap_err();
+        assert_eq!(
+            CircuitError::Cycle(vec!["b".to_string(), "c".to_string(), "d".to_string()]),
+            error
+        );
+        assert_eq!("cycle through wires b -> c -> d -> b", error.to_string());
+    }
+
+    #[test]
+    fn deep_circuit() {
+        let names: Vec<String> = (0..100_000)
+            .map(|n| {
+                let mut name = String::new();
+                let mut n: usize = n;
+                loop {
+                    name.push((b'a' + (n % 26) as u8) as char);
+                    n /= 26;
+                    if n == 0 {
+                        return name;
+                    }
+                }
+            })
+            .collect();
+        let circuit: Vec<_> = std::iter::once((Expression::Value(1), names[0].clone()))
+            .chain(
+                names
+                    .windows(2)
+                    .map(|pair| (Expression::Not(pair[0].clone()), pair[1].clone())),
+            )
+            .collect();
+        assert_eq!(!1, evaluate(&circuit).unwrap()[&names[99_999]]);
+    }
 }
@@ -1,4 +1,6 @@
+use crate::stream::for_each_line;
 use aoc_runner_derive::aoc;
+use std::io::{self, BufRead};
 
 const DOUBLE_QUOTE: u8 = 0x22;
 const BACKSLASH: u8 = 0x5c;
@@ -51,9 +53,31 @@ fn encoded_diff(line: &str) -> usize {
         })
 }
 
+/// Streaming [solve_part1], reading one line at a time.
+pub fn decoded_diff_from_reader(reader: impl BufRead) -> io::Result<usize> {
+    sum_from_reader(reader, decoded_diff)
+}
+
+/// Streaming [solve_part2], reading one line at a time.
+pub fn encoded_diff_from_reader(reader: impl BufRead) -> io::Result<usize> {
+    sum_from_reader(reader, encoded_diff)
+}
+
+fn sum_from_reader(reader: impl BufRead, diff: fn(&str) -> usize) -> io::Result<usize> {
+    let mut sum = 0;
+    for_each_line(reader, |line| {
+        if !line.is_empty() {
+            sum += diff(line)
+        }
+    })?;
+    Ok(sum)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::day8::{decoded_diff, encoded_diff};
+    use crate::day8::{
+        decoded_diff, decoded_diff_from_reader, encoded_diff, encoded_diff_from_reader,
+    };
 
     #[test]
     fn decoded() {
@@ -70,4 +94,11 @@ mod test {
         assert_eq!(6, encoded_diff("\"aaa\\\"aaa\""));
         assert_eq!(5, encoded_diff("\"\\x27\""));
     }
+
+    #[test]
+    fn streaming() {
+        let input = "\"\"\n\"abc\"\n\"aaa\\\"aaa\"\n\"\\x27\"\n";
+        assert_eq!(12, decoded_diff_from_reader(input.as_bytes()).unwrap());
+        assert_eq!(19, encoded_diff_from_reader(input.as_bytes()).unwrap());
+    }
 }
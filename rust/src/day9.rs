@@ -1,27 +1,25 @@
+use crate::parse::{self, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, line_ending, u32 as u32_nom};
+use nom::character::complete::{alpha1, u32 as u32_nom};
 use nom::combinator::map;
-use nom::multi::separated_list1;
 use nom::sequence::{preceded, separated_pair, tuple};
 use nom::IResult;
 use petgraph::prelude::UnGraphMap;
 
 #[aoc_generator(day9)]
-fn parse_routes(input: &str) -> Vec<Route> {
-    separated_list1(line_ending, route)(input).unwrap().1
+pub fn try_parse_routes(input: &str) -> Result<Vec<Route>, ParseError> {
+    parse::lines(9, input, route)
 }
 
 #[aoc(day9, part1)]
 pub fn solve_part1(routes: &[Route]) -> u32 {
-    let graph: UnGraphMap<&str, u32> = UnGraphMap::from_edges(routes.iter().map(|route| {
-        (
-            route.from.as_str().clone(),
-            route.to.as_str().clone(),
-            route.distance,
-        )
-    }));
+    let graph: UnGraphMap<&str, u32> = UnGraphMap::from_edges(
+        routes
+            .iter()
+            .map(|route| (route.from.as_str(), route.to.as_str(), route.distance)),
+    );
 
     graph.nodes().permutations(graph.node_count()).fold(
         u32::MAX,
@@ -39,13 +37,11 @@ pub fn solve_part1(routes: &[Route]) -> u32 {
 
 #[aoc(day9, part2)]
 pub fn solve_part2(routes: &[Route]) -> u32 {
-    let graph: UnGraphMap<&str, u32> = UnGraphMap::from_edges(routes.iter().map(|route| {
-        (
-            route.from.as_str().clone(),
-            route.to.as_str().clone(),
-            route.distance,
-        )
-    }));
+    let graph: UnGraphMap<&str, u32> = UnGraphMap::from_edges(
+        routes
+            .iter()
+            .map(|route| (route.from.as_str(), route.to.as_str(), route.distance)),
+    );
 
     graph.nodes().permutations(graph.node_count()).fold(
         u32::MIN,
@@ -81,7 +77,7 @@ fn city_to_city(input: &str) -> IResult<&str, (String, String)> {
 
 #[cfg(test)]
 mod test {
-    use crate::day9::{city_to_city, parse_routes, route, solve_part1, solve_part2};
+    use crate::day9::{city_to_city, route, solve_part1, solve_part2, try_parse_routes};
 
     const EXAMPLE: &str = r"London to Dublin = 464
 London to Belfast = 518
@@ -89,13 +85,13 @@ Dublin to Belfast = 141";
 
     #[test]
     fn part1() {
-        let routes = parse_routes(EXAMPLE);
+        let routes = try_parse_routes(EXAMPLE).unwrap();
         assert_eq!(605, solve_part1(&routes));
     }
 
     #[test]
     fn part2() {
-        let routes = parse_routes(EXAMPLE);
+        let routes = try_parse_routes(EXAMPLE).unwrap();
         assert_eq!(982, solve_part2(&routes));
     }
 
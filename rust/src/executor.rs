@@ -0,0 +1,208 @@
+use crate::registry::{prepare, puzzle, Answer, SolveError};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Solves every registered part of each day in `inputs` on `threads` workers.
+///
+/// A panicking solver only fails its own day/part, the remaining jobs still run.
+pub fn execute(inputs: &BTreeMap<u8, String>, threads: usize) -> Report {
+    let jobs: Vec<(u8, u8, &str)> = inputs
+        .iter()
+        .filter_map(|(&day, input)| puzzle(day).map(|puzzle| (puzzle, input)))
+        .flat_map(|(puzzle, input)| {
+            puzzle
+                .parts()
+                .into_iter()
+                .map(move |part| (puzzle.day, part, input.as_str()))
+        })
+        .collect();
+    let next = AtomicUsize::new(0);
+    let runs = Mutex::new(Vec::with_capacity(jobs.len()));
+
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, jobs.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(&(day, part, input)) = jobs.get(next.fetch_add(1, Ordering::Relaxed))
+                {
+                    let run = run(day, part, input);
+                    runs.lock().unwrap().push(run);
+                }
+            });
+        }
+    });
+    let wall = start.elapsed();
+
+    let mut runs = runs.into_inner().unwrap();
+    runs.sort_by_key(|run| (run.day, run.part));
+    Report { runs, wall }
+}
+
+fn run(day: u8, part: u8, input: &str) -> Run {
+    let mut generator = Duration::ZERO;
+    let mut solver = Duration::ZERO;
+    let outcome = catch_unwind(AssertUnwindSafe(|| {
+        let start = Instant::now();
+        let prepared = prepare(day, part, input);
+        generator = start.elapsed();
+        prepared.and_then(|prepared| {
+            let start = Instant::now();
+            let answer = prepared();
+            solver = start.elapsed();
+            answer
+        })
+    }));
+    let outcome = match outcome {
+        Ok(Ok(answer)) => Outcome::Solved(answer),
+        Ok(Err(e)) => Outcome::Failed(e),
+        Err(panic) => Outcome::Panicked(
+            panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string()),
+        ),
+    };
+    Run {
+        day,
+        part,
+        outcome,
+        generator,
+        solver,
+    }
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub runs: Vec<Run>,
+    pub wall: Duration,
+}
+
+#[derive(Debug)]
+pub struct Run {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+    pub generator: Duration,
+    pub solver: Duration,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Solved(Answer),
+    Failed(SolveError),
+    Panicked(String),
+}
+
+impl Report {
+    /// Sum of the generator and solver time of every run, as a single thread would have spent.
+    pub fn cpu(&self) -> Duration {
+        self.runs.iter().map(|run| run.generator + run.solver).sum()
+    }
+
+    pub fn table(&self) -> String {
+        let mut lines = vec![format!(
+            "{:>3} {:>4} {:>12} {:>12}  {}",
+            "day", "part", "generator", "solver", "answer"
+        )];
+        lines.extend(self.runs.iter().map(|run| {
+            let outcome = match &run.outcome {
+                Outcome::Solved(answer) => answer.to_string(),
+                Outcome::Failed(e) => format!("error: {e}"),
+                Outcome::Panicked(message) => format!("panicked: {message}"),
+            };
+            format!(
+                "{:>3} {:>4} {:>12} {:>12}  {}",
+                run.day,
+                run.part,
+                format!("{:.3?}", run.generator),
+                format!("{:.3?}", run.solver),
+                outcome
+            )
+        }));
+        lines.push(format!(
+            "{} runs, wall {:.3?}, cpu {:.3?}",
+            self.runs.len(),
+            self.wall,
+            self.cpu()
+        ));
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "wall_ns": self.wall.as_nanos() as u64,
+            "cpu_ns": self.cpu().as_nanos() as u64,
+            "runs": self.runs.iter().map(|run| {
+                let (status, answer, error) = match &run.outcome {
+                    Outcome::Solved(Answer::Integer(value)) => ("solved", json!(value), Value::Null),
+                    Outcome::Solved(Answer::String(value)) => ("solved", json!(value), Value::Null),
+                    Outcome::Failed(e) => ("failed", Value::Null, json!(e.to_string())),
+                    Outcome::Panicked(message) => ("panicked", Value::Null, json!(message)),
+                };
+                json!({
+                    "day": run.day,
+                    "part": run.part,
+                    "status": status,
+                    "answer": answer,
+                    "error": error,
+                    "generator_ns": run.generator.as_nanos() as u64,
+                    "solver_ns": run.solver.as_nanos() as u64,
+                })
+            }).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::executor::{execute, Outcome};
+    use crate::registry::Answer;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn runs_every_part() {
+        let inputs = BTreeMap::from([
+            (1, "()())".to_string()),
+            (2, "2x3x4".to_string()),
+            (25, "To continue, please consult the code grid in the manual.  Enter the code at row 2, column 1.".to_string()),
+        ]);
+        let report = execute(&inputs, 4);
+        let outcomes: Vec<_> = report
+            .runs
+            .iter()
+            .map(|run| (run.day, run.part, &run.outcome))
+            .collect();
+        assert_eq!(
+            vec![
+                (1, 1, &Outcome::Solved(Answer::Integer(-1))),
+                (1, 2, &Outcome::Solved(Answer::Integer(5))),
+                (2, 1, &Outcome::Solved(Answer::Integer(58))),
+                (2, 2, &Outcome::Solved(Answer::Integer(34))),
+                (25, 1, &Outcome::Solved(Answer::Integer(31916031))),
+            ],
+            outcomes
+        );
+    }
+
+    #[test]
+    fn isolates_failures() {
+        let inputs = BTreeMap::from([
+            (1, "(x".to_string()),
+            (2, "2x3".to_string()),
+            (3, "^x".to_string()),
+        ]);
+        let report = execute(&inputs, 2);
+        assert!(matches!(report.runs[0].outcome, Outcome::Failed(_)));
+        assert!(matches!(report.runs[2].outcome, Outcome::Failed(_)));
+        assert!(matches!(report.runs[4].outcome, Outcome::Panicked(_)));
+        assert!(matches!(report.runs[5].outcome, Outcome::Panicked(_)));
+        assert_eq!(6, report.runs.len());
+        assert_eq!(6, report.to_json()["runs"].as_array().unwrap().len());
+    }
+}
@@ -0,0 +1,367 @@
+use crate::parse::ParseError;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// `(row, column)` for dense grids, any pair of signed coordinates for sparse ones.
+pub type Point = (i32, i32);
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Connectivity {
+    /// Up, down, left and right.
+    Four,
+    /// Orthogonal and diagonal.
+    Eight,
+}
+
+impl Connectivity {
+    pub fn offsets(&self) -> &'static [Point] {
+        match self {
+            Connectivity::Four => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// All points neighboring `point`, regardless of any bounds.
+pub fn neighbors((row, column): Point, connectivity: Connectivity) -> impl Iterator<Item = Point> {
+    connectivity
+        .offsets()
+        .iter()
+        .map(move |(dr, dc)| (row + dr, column + dc))
+}
+
+/// Inclusive rectangle between two corners.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Rect {
+    pub from: Point,
+    pub to: Point,
+}
+
+impl Rect {
+    pub fn new(from: Point, to: Point) -> Self {
+        Rect { from, to }
+    }
+
+    pub fn contains(&self, (row, column): Point) -> bool {
+        (self.from.0..=self.to.0).contains(&row) && (self.from.1..=self.to.1).contains(&column)
+    }
+
+    pub fn height(&self) -> usize {
+        (self.to.0 - self.from.0 + 1).max(0) as usize
+    }
+
+    pub fn width(&self) -> usize {
+        (self.to.1 - self.from.1 + 1).max(0) as usize
+    }
+
+    pub fn area(&self) -> usize {
+        self.height() * self.width()
+    }
+
+    /// Points in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Rect { from, to } = *self;
+        (from.0..=to.0).flat_map(move |row| (from.1..=to.1).map(move |column| (row, column)))
+    }
+}
+
+/// Dense, fixed size grid stored on the heap in row-major order.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new((0, 0), (self.height as i32 - 1, self.width as i32 - 1))
+    }
+
+    pub fn contains(&self, (row, column): Point) -> bool {
+        0 <= row && (row as usize) < self.height && 0 <= column && (column as usize) < self.width
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.0 as usize * self.width + point.1 as usize)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index(point).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Sets the cell at `point`, ignoring points outside the grid.
+    pub fn set(&mut self, point: Point, value: T) {
+        if let Some(cell) = self.get_mut(point) {
+            *cell = value;
+        }
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.bounds().points().zip(self.cells.iter())
+    }
+
+    /// In-bounds neighbors of `point`.
+    pub fn neighbors(
+        &self,
+        point: Point,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Point> + '_ {
+        neighbors(point, connectivity).filter(|&p| self.contains(p))
+    }
+
+    /// Cells inside `rect`, clipped to the grid.
+    pub fn view(&self, rect: Rect) -> impl Iterator<Item = (Point, &T)> {
+        self.clip(rect)
+            .points()
+            .map(|point| (point, &self.cells[self.index(point).unwrap()]))
+    }
+
+    /// Applies `update` to every cell inside `rect`, clipped to the grid.
+    pub fn update(&mut self, rect: Rect, mut update: impl FnMut(&mut T)) {
+        let rect = self.clip(rect);
+        if rect.area() == 0 {
+            return;
+        }
+        for row in rect.from.0..=rect.to.0 {
+            let start = row as usize * self.width;
+            self.cells[start + rect.from.1 as usize..=start + rect.to.1 as usize]
+                .iter_mut()
+                .for_each(&mut update);
+        }
+    }
+
+    fn clip(&self, rect: Rect) -> Rect {
+        let bounds = self.bounds();
+        Rect::new(
+            (rect.from.0.max(0), rect.from.1.max(0)),
+            (rect.to.0.min(bounds.to.0), rect.to.1.min(bounds.to.1)),
+        )
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<bool> {
+    /// Parses rows of `#` (on) and `.` (off) cells.
+    pub fn parse(day: u8, input: &str) -> Result<Grid<bool>, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for (row, line) in input.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            for (column, c) in line.chars().enumerate() {
+                cells.push(match c {
+                    '#' => true,
+                    '.' => false,
+                    _ => return Err(ParseError::new(day, row + 1, column + 1, "`#` or `.`")),
+                });
+            }
+            let line_width = line.chars().count();
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(ParseError::new(
+                        day,
+                        row + 1,
+                        line_width.min(width) + 1,
+                        format!("a row of {width} cells"),
+                    ))
+                }
+                _ => {}
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+}
+
+impl Display for Grid<bool> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<String> = self
+            .cells
+            .chunks(self.width.max(1))
+            .map(|row| row.iter().map(|&on| if on { '#' } else { '.' }).collect())
+            .collect();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+/// Unbounded grid that only stores the cells that were set.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&point, value)| (point, value))
+    }
+
+    /// Smallest rectangle containing every set cell.
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut points = self.cells.keys();
+        let &first = points.next()?;
+        Some(
+            points.fold(Rect::new(first, first), |rect, &(row, column)| {
+                Rect::new(
+                    (rect.from.0.min(row), rect.from.1.min(column)),
+                    (rect.to.0.max(row), rect.to.1.max(column)),
+                )
+            }),
+        )
+    }
+
+    /// Cells inside `rect` that have been set.
+    pub fn view(&self, rect: Rect) -> impl Iterator<Item = (Point, &T)> {
+        self.iter().filter(move |(point, _)| rect.contains(*point))
+    }
+}
+
+impl<T: Default> SparseGrid<T> {
+    /// The cell at `point`, inserting the default value first when unset.
+    pub fn entry(&mut self, point: Point) -> &mut T {
+        self.cells.entry(point).or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::{Connectivity, Grid, Rect, SparseGrid};
+    use crate::parse::ParseError;
+
+    const GLIDER: &str = ".#.\n..#\n###";
+
+    #[test]
+    fn parse_and_display() {
+        let grid = Grid::parse(0, GLIDER).unwrap();
+        assert_eq!(3, grid.width());
+        assert_eq!(3, grid.height());
+        assert_eq!(Some(&true), grid.get((0, 1)));
+        assert_eq!(Some(&false), grid.get((1, 1)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(GLIDER, grid.to_string());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Some(ParseError::new(0, 2, 2, "`#` or `.`")),
+            Grid::parse(0, "..\n.x").err()
+        );
+        assert_eq!(
+            Some(ParseError::new(0, 2, 2, "a row of 2 cells")),
+            Grid::parse(0, "..\n.").err()
+        );
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(2, grid.neighbors((0, 0), Connectivity::Four).count());
+        assert_eq!(3, grid.neighbors((0, 0), Connectivity::Eight).count());
+        assert_eq!(4, grid.neighbors((1, 1), Connectivity::Four).count());
+        assert_eq!(8, grid.neighbors((1, 1), Connectivity::Eight).count());
+        assert_eq!(5, grid.neighbors((2, 1), Connectivity::Eight).count());
+    }
+
+    #[test]
+    fn rectangles() {
+        let mut grid = Grid::new(4, 3, 0u32);
+        grid.update(Rect::new((1, 1), (5, 2)), |cell| *cell += 1);
+        assert_eq!(4, grid.values().sum::<u32>());
+        assert_eq!(
+            vec![((1, 1), &1), ((1, 2), &1), ((1, 3), &0)],
+            grid.view(Rect::new((1, 1), (1, 7))).collect::<Vec<_>>()
+        );
+        assert_eq!(6, Rect::new((1, 1), (2, 3)).area());
+        assert_eq!(0, Rect::new((1, 1), (0, 3)).area());
+    }
+
+    #[test]
+    fn sparse() {
+        let mut grid: SparseGrid<u32> = SparseGrid::new();
+        assert_eq!(None, grid.bounds());
+        *grid.entry((-2, 3)) += 1;
+        *grid.entry((4, -1)) += 1;
+        *grid.entry((4, -1)) += 1;
+        assert_eq!(2, grid.len());
+        assert_eq!(Some(&2), grid.get((4, -1)));
+        assert_eq!(Some(Rect::new((-2, -1), (4, 3))), grid.bounds());
+    }
+}
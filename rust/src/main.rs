@@ -1,7 +1,358 @@
-extern crate advent_of_code_2015;
-extern crate aoc_runner;
-extern crate aoc_runner_derive;
+use advent_of_code_2015::answers::AnswerKey;
+use advent_of_code_2015::executor::{execute, Outcome};
+use advent_of_code_2015::{puzzle, puzzles, solve, Answer, Puzzle};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::Read;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use std::{env, fs, io, thread};
 
-use aoc_runner_derive::aoc_main;
+const USAGE: &str = "usage: advent-of-code-2015 <command> [options]
 
-aoc_main! { lib = advent_of_code_2015 }
+commands:
+  run      solve one day (both parts unless --part is given)
+  verify   solve one day and compare against an answers file
+  bench    solve one day repeatedly and report timings
+  all      solve every day that has an input in --inputs, in parallel
+  explain  describe a day, or every day when --day is omitted
+
+options:
+  --day <n>           puzzle day, 1-25
+  --part <n>          puzzle part, 1 or 2
+  --input <file|->    puzzle input, `-` reads stdin [default: <inputs>/day<n>.txt]
+  --inputs <dir>      directory of day<n>.txt inputs [default: input/2015]
+  --answers <file>    expected answers, `part1 = ..` / `part2 = ..` lines
+  --iterations <n>    repetitions for bench [default: 10]
+  --threads <n>       worker threads for all [default: available cores]
+  --json              print machine-readable JSON instead of text";
+
+fn main() -> ExitCode {
+    match parse_args(env::args().skip(1)).and_then(|(command, options)| command.run(&options)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    Run,
+    Verify,
+    Bench,
+    All,
+    Explain,
+    Help,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<String>,
+    inputs: String,
+    answers: Option<String>,
+    iterations: usize,
+    threads: usize,
+    json: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            day: None,
+            part: None,
+            input: None,
+            inputs: "input/2015".to_string(),
+            answers: None,
+            iterations: 10,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            json: false,
+        }
+    }
+}
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> CliResult<(Command, Options)> {
+    let command = match args.next().as_deref() {
+        Some("run") => Command::Run,
+        Some("verify") => Command::Verify,
+        Some("bench") => Command::Bench,
+        Some("all") => Command::All,
+        Some("explain") => Command::Explain,
+        Some("help" | "-h" | "--help") | None => Command::Help,
+        Some(other) => return Err(format!("unknown command `{other}`\n\n{USAGE}").into()),
+    };
+    let mut options = Options::default();
+    while let Some(flag) = args.next() {
+        if flag == "--json" {
+            options.json = true;
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for `{flag}`"))?;
+        match flag.as_str() {
+            "--day" => options.day = Some(value.parse()?),
+            "--part" => options.part = Some(value.parse()?),
+            "--input" => options.input = Some(value),
+            "--inputs" => options.inputs = value,
+            "--answers" => options.answers = Some(value),
+            "--iterations" => options.iterations = value.parse()?,
+            "--threads" => options.threads = value.parse()?,
+            _ => return Err(format!("unknown option `{flag}`\n\n{USAGE}").into()),
+        }
+    }
+    Ok((command, options))
+}
+
+impl Command {
+    /// Runs the command, returning `false` when a verification failed.
+    fn run(&self, options: &Options) -> CliResult<bool> {
+        match self {
+            Command::Run => run(options),
+            Command::Verify => verify(options),
+            Command::Bench => bench(options),
+            Command::All => all(options),
+            Command::Explain => explain(options),
+            Command::Help => {
+                println!("{USAGE}");
+                Ok(true)
+            }
+        }
+    }
+}
+
+fn selected_puzzle(options: &Options) -> CliResult<&'static Puzzle> {
+    let day = options.day.ok_or("`--day` is required")?;
+    puzzle(day).ok_or_else(|| format!("no puzzle for day {day}").into())
+}
+
+fn selected_parts(puzzle: &Puzzle, options: &Options) -> Vec<u8> {
+    match options.part {
+        Some(part) => vec![part],
+        None => puzzle.parts(),
+    }
+}
+
+fn read_input(puzzle: &Puzzle, options: &Options) -> CliResult<String> {
+    match options.input.as_deref() {
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+        Some(path) => Ok(fs::read_to_string(path)?),
+        None => Ok(fs::read_to_string(default_input(puzzle, options))?),
+    }
+}
+
+fn default_input(puzzle: &Puzzle, options: &Options) -> String {
+    format!("{}/day{}.txt", options.inputs, puzzle.day)
+}
+
+fn answer_json(answer: &Answer) -> Value {
+    match answer {
+        Answer::Integer(value) => json!(value),
+        Answer::String(value) => json!(value),
+    }
+}
+
+fn run(options: &Options) -> CliResult<bool> {
+    let puzzle = selected_puzzle(options)?;
+    let input = read_input(puzzle, options)?;
+    let mut results = vec![];
+    for part in selected_parts(puzzle, options) {
+        let answer = solve(puzzle.day, part, &input)?;
+        if !options.json {
+            println!("day {} part {part}: {answer}", puzzle.day);
+        }
+        results.push(json!({"day": puzzle.day, "part": part, "answer": answer_json(&answer)}));
+    }
+    if options.json {
+        println!("{}", Value::Array(results));
+    }
+    Ok(true)
+}
+
+fn verify(options: &Options) -> CliResult<bool> {
+    let puzzle = selected_puzzle(options)?;
+    let input = read_input(puzzle, options)?;
+    let answers = options
+        .answers
+        .as_deref()
+        .ok_or("`--answers` is required")?;
+    let key = AnswerKey::parse(puzzle.day, &fs::read_to_string(answers)?)?;
+    let mut passed = true;
+    let mut results = vec![];
+    for part in selected_parts(puzzle, options) {
+        let answer = solve(puzzle.day, part, &input)?;
+        let expected = key.get(part);
+        let ok = expected == Some(&answer);
+        passed &= ok;
+        if !options.json {
+            match expected {
+                Some(_) if ok => println!("day {} part {part}: {answer} ok", puzzle.day),
+                Some(expected) => println!(
+                    "day {} part {part}: {answer} MISMATCH, expected {expected}",
+                    puzzle.day
+                ),
+                None => println!(
+                    "day {} part {part}: {answer} no expected answer",
+                    puzzle.day
+                ),
+            }
+        }
+        results.push(json!({
+            "day": puzzle.day,
+            "part": part,
+            "answer": answer_json(&answer),
+            "expected": expected.map(answer_json),
+            "ok": ok,
+        }));
+    }
+    if options.json {
+        println!("{}", Value::Array(results));
+    }
+    Ok(passed)
+}
+
+fn bench(options: &Options) -> CliResult<bool> {
+    let puzzle = selected_puzzle(options)?;
+    let input = read_input(puzzle, options)?;
+    let iterations = options.iterations.max(1);
+    let mut results = vec![];
+    for part in selected_parts(puzzle, options) {
+        let mut timings = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            solve(puzzle.day, part, &input)?;
+            timings.push(start.elapsed());
+        }
+        let min = *timings.iter().min().unwrap();
+        let max = *timings.iter().max().unwrap();
+        let mean = timings.iter().sum::<Duration>() / iterations as u32;
+        if !options.json {
+            println!(
+                "day {} part {part}: {iterations} runs, min {min:?}, mean {mean:?}, max {max:?}",
+                puzzle.day
+            );
+        }
+        results.push(json!({
+            "day": puzzle.day,
+            "part": part,
+            "iterations": iterations,
+            "min_ns": min.as_nanos() as u64,
+            "mean_ns": mean.as_nanos() as u64,
+            "max_ns": max.as_nanos() as u64,
+        }));
+    }
+    if options.json {
+        println!("{}", Value::Array(results));
+    }
+    Ok(true)
+}
+
+fn all(options: &Options) -> CliResult<bool> {
+    let mut inputs = BTreeMap::new();
+    let mut skipped = vec![];
+    for puzzle in puzzles() {
+        let path = default_input(puzzle, options);
+        if Path::new(&path).exists() {
+            inputs.insert(puzzle.day, fs::read_to_string(&path)?);
+        } else {
+            skipped.push(puzzle.day);
+        }
+    }
+    let report = execute(&inputs, options.threads);
+    if options.json {
+        let mut json = report.to_json();
+        json["skipped"] = json!(skipped);
+        println!("{json}");
+    } else {
+        for day in skipped {
+            println!("day {day}: skipped, no input in {}", options.inputs);
+        }
+        println!("{}", report.table());
+    }
+    Ok(report
+        .runs
+        .iter()
+        .all(|run| matches!(run.outcome, Outcome::Solved(_))))
+}
+
+fn explain(options: &Options) -> CliResult<bool> {
+    let selected = match options.day {
+        Some(_) => vec![selected_puzzle(options)?],
+        None => puzzles().iter().collect(),
+    };
+    if options.json {
+        let days = selected
+            .iter()
+            .map(|puzzle| {
+                json!({
+                    "day": puzzle.day,
+                    "title": puzzle.title,
+                    "input": format!("{:?}", puzzle.input),
+                    "parts": puzzle.parts(),
+                })
+            })
+            .collect();
+        println!("{}", Value::Array(days));
+    } else {
+        for puzzle in selected {
+            println!(
+                "day {:>2}: {} (input: {:?}, parts: {:?})",
+                puzzle.day,
+                puzzle.title,
+                puzzle.input,
+                puzzle.parts()
+            );
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_args, Command, Options};
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn parse_run() {
+        let (command, options) = parse_args(args("run --day 7 --part 2 --input - --json")).unwrap();
+        assert_eq!(Command::Run, command);
+        assert_eq!(
+            Options {
+                day: Some(7),
+                part: Some(2),
+                input: Some("-".to_string()),
+                json: true,
+                ..Options::default()
+            },
+            options
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_args(args("launch")).is_err());
+        assert!(parse_args(args("run --day")).is_err());
+        assert!(parse_args(args("run --day seven")).is_err());
+        assert!(parse_args(args("bench --speed 2")).is_err());
+    }
+
+    #[test]
+    fn parse_help() {
+        assert_eq!(Command::Help, parse_args(args("")).unwrap().0);
+    }
+}
@@ -0,0 +1,168 @@
+use nom::error::ErrorKind;
+use nom::{Err, Parser};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Location and expectation of the first malformed token in a puzzle input.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, expected: impl Into<String>) -> Self {
+        ParseError {
+            day,
+            line,
+            column,
+            expected: expected.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {}: line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses every non-blank line of `input` with `parser`, requiring the whole line to be consumed
+/// apart from leading and trailing whitespace. Columns in errors still count that whitespace.
+pub(crate) fn lines<'a, O, P>(day: u8, input: &'a str, parser: P) -> Result<Vec<O>, ParseError>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    lines_checked(day, input, parser, Ok)
+}
+
+/// Like [lines], then passes every parsed line to `check`, which rejects it by returning the
+/// offending slice of the line along with what was expected instead.
+pub(crate) fn lines_checked<'a, O, T, P, C>(
+    day: u8,
+    input: &'a str,
+    mut parser: P,
+    mut check: C,
+) -> Result<Vec<T>, ParseError>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+    C: FnMut(O) -> Result<T, (&'a str, String)>,
+{
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            let trimmed = line.trim();
+            let (column, expected) = match parser.parse(trimmed) {
+                Ok(("", output)) => match check(output) {
+                    Ok(checked) => return Ok(checked),
+                    Err((token, expected)) => (start(line, token), expected),
+                },
+                Ok((rest, _)) => (start(line, rest), expected(ErrorKind::Eof).into()),
+                Err(Err::Error(e) | Err::Failure(e)) => {
+                    (start(line, e.input), expected(e.code).into())
+                }
+                Err(Err::Incomplete(_)) => (
+                    start(line, &trimmed[trimmed.len()..]),
+                    expected(ErrorKind::Complete).into(),
+                ),
+            };
+            Err(ParseError::new(day, idx + 1, column + 1, expected))
+        })
+        .collect()
+}
+
+/// Parses the whole of `input` (ignoring trailing whitespace) with `parser`.
+pub(crate) fn complete<'a, O, P>(day: u8, input: &'a str, mut parser: P) -> Result<O, ParseError>
+where
+    P: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    let input = input.trim_end();
+    let (rest, kind) = match parser.parse(input) {
+        Ok(("", output)) => return Ok(output),
+        Ok((rest, _)) => (rest, ErrorKind::Eof),
+        Err(Err::Error(e) | Err::Failure(e)) => (e.input, e.code),
+        Err(Err::Incomplete(_)) => ("", ErrorKind::Complete),
+    };
+    let consumed = &input[..input.len() - rest.len()];
+    let line = consumed.matches('\n').count() + 1;
+    let line_start = consumed.rfind('\n').map_or(0, |idx| idx + 1);
+    let expected = match kind {
+        ErrorKind::Eof => "end of input",
+        kind => expected(kind),
+    };
+    Err(ParseError::new(
+        day,
+        line,
+        consumed[line_start..].chars().count() + 1,
+        expected,
+    ))
+}
+
+/// Character offset of `token`, a slice of `line`, within `line`.
+fn start(line: &str, token: &str) -> usize {
+    line[..token.as_ptr() as usize - line.as_ptr() as usize]
+        .chars()
+        .count()
+}
+
+fn expected(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::Eof => "end of line",
+        ErrorKind::Complete => "more input",
+        ErrorKind::Tag => "keyword",
+        ErrorKind::Char => "punctuation",
+        ErrorKind::Digit => "number",
+        ErrorKind::Alpha => "name",
+        ErrorKind::AlphaNumeric => "name",
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+        ErrorKind::CrLf => "line ending",
+        ErrorKind::Verify => "valid name",
+        ErrorKind::Alt => "one of the known keywords",
+        _ => "valid input",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse::{complete, lines, ParseError};
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{line_ending, u32 as u32_nom};
+    use nom::sequence::separated_pair;
+
+    #[test]
+    fn reports_line_and_column() {
+        let input = "1x2\n3x4\n5y6\n";
+        assert_eq!(
+            Err(ParseError::new(0, 3, 2, "keyword")),
+            lines(0, input, separated_pair(u32_nom, tag("x"), u32_nom))
+        );
+    }
+
+    #[test]
+    fn trailing_garbage() {
+        assert_eq!(
+            Err(ParseError::new(0, 1, 4, "end of line")),
+            lines(0, "1x2!", separated_pair(u32_nom, tag("x"), u32_nom))
+        );
+    }
+
+    #[test]
+    fn complete_input() {
+        let parser = || separated_pair(u32_nom, line_ending, u32_nom);
+        assert_eq!(Ok((1, 2)), complete(0, "1\n2\n", parser()));
+        assert_eq!(
+            Err(ParseError::new(0, 2, 1, "number")),
+            complete(0, "1\nx", parser())
+        );
+    }
+}
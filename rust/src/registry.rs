@@ -0,0 +1,456 @@
+use crate::day1::FloorTrace;
+use crate::day7::CircuitError;
+use crate::parse::ParseError;
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,
+};
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Solves `part` of `day` for the given puzzle input.
+///
+/// Malformed input is reported as an error for every day read through a generator. Days 3 and 8
+/// solve the raw input and still panic on a character outside the puzzle's alphabet (a direction
+/// or an escape sequence), which [crate::executor::execute] reports as a panic.
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, SolveError> {
+    prepare(day, part, input)?()
+}
+
+/// Runs the generator for `part` of `day`, returning the solver ready to run on its output.
+///
+/// Trailing newlines are stripped from the input, as the aoc runner does.
+pub fn prepare(day: u8, part: u8, input: &str) -> Result<Prepared<'_>, SolveError> {
+    let solver = puzzle(day)
+        .and_then(|puzzle| puzzle.solver(part))
+        .ok_or(SolveError::UnknownPuzzle { day, part })?;
+    Ok(solver(input.trim_end_matches(['\n', '\r']))?)
+}
+
+pub fn puzzles() -> &'static [Puzzle] {
+    &PUZZLES
+}
+
+pub fn puzzle(day: u8) -> Option<&'static Puzzle> {
+    PUZZLES.iter().find(|puzzle| puzzle.day == day)
+}
+
+/// A solver with its input already generated, mirroring the `aoc_generator`/`aoc` split.
+pub type Prepared<'a> = Box<dyn FnOnce() -> Result<Answer, SolveError> + 'a>;
+
+pub type Solver = for<'a> fn(&'a str) -> Result<Prepared<'a>, ParseError>;
+
+#[derive(Debug)]
+pub struct Puzzle {
+    pub day: u8,
+    pub title: &'static str,
+    pub input: InputKind,
+    part1: Solver,
+    part2: Option<Solver>,
+}
+
+impl Puzzle {
+    pub fn solver(&self, part: u8) -> Option<Solver> {
+        match part {
+            1 => Some(self.part1),
+            2 => self.part2,
+            _ => None,
+        }
+    }
+
+    /// Parts with a solver, day 25 only has one.
+    pub fn parts(&self) -> Vec<u8> {
+        (1..=2)
+            .filter(|&part| self.solver(part).is_some())
+            .collect()
+    }
+}
+
+/// Shape of the puzzle input a day expects.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum InputKind {
+    /// A single line of text.
+    Text,
+    /// A single number.
+    Number,
+    /// One record per line.
+    Lines,
+    /// A JSON document.
+    Json,
+    /// Rows of `#` and `.` cells.
+    Grid,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Answer {
+    Integer(i64),
+    String(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{value}"),
+            Answer::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// What solvers return, turned into an [Answer].
+trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, SolveError>;
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(impl IntoAnswer for $t {
+            fn into_answer(self) -> Result<Answer, SolveError> {
+                i64::try_from(self)
+                    .map(Answer::Integer)
+                    .map_err(|_| SolveError::TooLarge(self.to_string()))
+            }
+        })*
+    };
+}
+integer_answer!(u16, u32, u64, usize, i32, i64);
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Result<Answer, SolveError> {
+        Ok(Answer::String(self))
+    }
+}
+
+/// `None` when the input has no answer.
+impl<A: IntoAnswer> IntoAnswer for Option<A> {
+    fn into_answer(self) -> Result<Answer, SolveError> {
+        self.ok_or(SolveError::NoAnswer)?.into_answer()
+    }
+}
+
+impl<A: IntoAnswer, E: Into<SolveError>> IntoAnswer for Result<A, E> {
+    fn into_answer(self) -> Result<Answer, SolveError> {
+        self.map_err(Into::into)?.into_answer()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SolveError {
+    UnknownPuzzle {
+        day: u8,
+        part: u8,
+    },
+    Parse(ParseError),
+    Circuit(CircuitError),
+    /// The input parses, but leads to no answer.
+    NoAnswer,
+    /// The answer, in decimal, does not fit in an [Answer::Integer].
+    TooLarge(String),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::UnknownPuzzle { day, part } => {
+                write!(f, "no solver for day {day} part {part}")
+            }
+            SolveError::Parse(e) => write!(f, "{e}"),
+            SolveError::Circuit(e) => write!(f, "{e}"),
+            SolveError::NoAnswer => write!(f, "no answer for this input"),
+            SolveError::TooLarge(answer) => write!(f, "answer {answer} does not fit in 64 bits"),
+        }
+    }
+}
+
+impl Error for SolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SolveError::Parse(e) => Some(e),
+            SolveError::Circuit(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        SolveError::Parse(e)
+    }
+}
+
+impl From<CircuitError> for SolveError {
+    fn from(e: CircuitError) -> Self {
+        SolveError::Circuit(e)
+    }
+}
+
+/// Prepares a `solver` that works directly on the puzzle input.
+fn raw<'a, A: IntoAnswer + 'a>(
+    input: &'a str,
+    solver: fn(&str) -> A,
+) -> Result<Prepared<'a>, ParseError> {
+    Ok(Box::new(move || solver(input).into_answer()))
+}
+
+/// Prepares `solver` over the output of a fallible `generator`, like an `aoc_generator` pair.
+fn generated<'a, T, U, A>(
+    input: &'a str,
+    generator: fn(&str) -> Result<T, ParseError>,
+    solver: fn(&U) -> A,
+) -> Result<Prepared<'a>, ParseError>
+where
+    T: Borrow<U> + 'a,
+    U: ?Sized + 'a,
+    A: IntoAnswer + 'a,
+{
+    let generated = generator(input)?;
+    Ok(Box::new(move || solver(generated.borrow()).into_answer()))
+}
+
+static PUZZLES: [Puzzle; 25] = [
+    Puzzle {
+        day: 1,
+        title: "Not Quite Lisp",
+        input: InputKind::Text,
+        part1: |input| generated(input, day1::trace, |trace: &FloorTrace| trace.floor),
+        part2: Some(|input| generated(input, day1::trace, FloorTrace::basement)),
+    },
+    Puzzle {
+        day: 2,
+        title: "I Was Told There Would Be No Math",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day2::try_parse_gifts, day2::solve_part1),
+        part2: Some(|input| generated(input, day2::try_parse_gifts, day2::solve_part2)),
+    },
+    Puzzle {
+        day: 3,
+        title: "Perfectly Spherical Houses in a Vacuum",
+        input: InputKind::Text,
+        part1: |input| raw(input, day3::solve_part1),
+        part2: Some(|input| raw(input, day3::solve_part2)),
+    },
+    Puzzle {
+        day: 4,
+        title: "The Ideal Stocking Stuffer",
+        input: InputKind::Text,
+        part1: |input| raw(input, day4::solve_part1),
+        part2: Some(|input| raw(input, day4::solve_part2)),
+    },
+    Puzzle {
+        day: 5,
+        title: "Doesn't He Have Intern-Elves For This?",
+        input: InputKind::Lines,
+        part1: |input| raw(input, day5::solve_part1),
+        part2: Some(|input| raw(input, day5::solve_part2)),
+    },
+    Puzzle {
+        day: 6,
+        title: "Probably a Fire Hazard",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day6::try_parse_instructions, day6::solve_part1),
+        part2: Some(|input| generated(input, day6::try_parse_instructions, day6::solve_part2)),
+    },
+    Puzzle {
+        day: 7,
+        title: "Some Assembly Required",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day7::try_parse_instructions, day7::solve_part1),
+        part2: Some(|input| generated(input, day7::try_parse_instructions, day7::solve_part2)),
+    },
+    Puzzle {
+        day: 8,
+        title: "Matchsticks",
+        input: InputKind::Lines,
+        part1: |input| raw(input, day8::solve_part1),
+        part2: Some(|input| raw(input, day8::solve_part2)),
+    },
+    Puzzle {
+        day: 9,
+        title: "All in a Single Night",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day9::try_parse_routes, day9::solve_part1),
+        part2: Some(|input| generated(input, day9::try_parse_routes, day9::solve_part2)),
+    },
+    Puzzle {
+        day: 10,
+        title: "Elves Look, Elves Say",
+        input: InputKind::Text,
+        part1: |input| raw(input, day10::solve_part1),
+        part2: Some(|input| raw(input, day10::solve_part2)),
+    },
+    Puzzle {
+        day: 11,
+        title: "Corporate Policy",
+        input: InputKind::Text,
+        part1: |input| raw(input, day11::solve_part1),
+        part2: Some(|input| raw(input, day11::solve_part2)),
+    },
+    Puzzle {
+        day: 12,
+        title: "JSAbacusFramework.io",
+        input: InputKind::Json,
+        part1: |input| generated(input, day12::try_parse_document, day12::solve_part1),
+        part2: Some(|input| generated(input, day12::try_parse_document, day12::solve_part2)),
+    },
+    Puzzle {
+        day: 13,
+        title: "Knights of the Dinner Table",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day13::try_parse_happy_dances, day13::solve_part1),
+        part2: Some(|input| generated(input, day13::try_parse_happy_dances, day13::solve_part2)),
+    },
+    Puzzle {
+        day: 14,
+        title: "Reindeer Olympics",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day14::try_parse_olympic_herd, day14::solve_part1),
+        part2: Some(|input| generated(input, day14::try_parse_olympic_herd, day14::solve_part2)),
+    },
+    Puzzle {
+        day: 15,
+        title: "Science for Hungry People",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day15::try_parse_ingredients, day15::solve_part1),
+        part2: Some(|input| generated(input, day15::try_parse_ingredients, day15::solve_part2)),
+    },
+    Puzzle {
+        day: 16,
+        title: "Aunt Sue",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day16::try_parse_aunts, day16::solve_part1),
+        part2: Some(|input| generated(input, day16::try_parse_aunts, day16::solve_part2)),
+    },
+    Puzzle {
+        day: 17,
+        title: "No Such Thing as Too Much",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day17::try_parse_containers, day17::solve_part1),
+        part2: Some(|input| generated(input, day17::try_parse_containers, day17::solve_part2)),
+    },
+    Puzzle {
+        day: 18,
+        title: "Like a GIF For Your Yard",
+        input: InputKind::Grid,
+        part1: |input| generated(input, day18::try_parse_grid, day18::solve_part1),
+        part2: Some(|input| generated(input, day18::try_parse_grid, day18::solve_part2)),
+    },
+    Puzzle {
+        day: 19,
+        title: "Medicine for Rudolph",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day19::try_parse_rudolph_meds, day19::solve_part1),
+        part2: Some(|input| generated(input, day19::try_parse_rudolph_meds, day19::part2)),
+    },
+    Puzzle {
+        day: 20,
+        title: "Infinite Elves and Infinite Houses",
+        input: InputKind::Number,
+        part1: |input| generated(input, day20::try_parse_presents, day20::solve_part1),
+        part2: Some(|input| generated(input, day20::try_parse_presents, day20::solve_part2)),
+    },
+    Puzzle {
+        day: 21,
+        title: "RPG Simulator 20XX",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day21::try_parse_boss, day21::solve_part1),
+        part2: Some(|input| generated(input, day21::try_parse_boss, day21::solve_part2)),
+    },
+    Puzzle {
+        day: 22,
+        title: "Wizard Simulator 20XX",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day22::try_parse_boss, day22::solve_part1),
+        part2: Some(|input| generated(input, day22::try_parse_boss, day22::solve_part2)),
+    },
+    Puzzle {
+        day: 23,
+        title: "Opening the Turing Lock",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day23::try_parse_instructions, day23::solve_part1),
+        part2: Some(|input| generated(input, day23::try_parse_instructions, day23::solve_part2)),
+    },
+    Puzzle {
+        day: 24,
+        title: "It Hangs in the Balance",
+        input: InputKind::Lines,
+        part1: |input| generated(input, day24::try_parse_packages, day24::solve_part1),
+        part2: Some(|input| generated(input, day24::try_parse_packages, day24::solve_part2)),
+    },
+    Puzzle {
+        day: 25,
+        title: "Let It Snow",
+        input: InputKind::Text,
+        part1: |input| generated(input, day25::try_parse_row_column, day25::solve_part1),
+        part2: None,
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use crate::parse::ParseError;
+    use crate::registry::{puzzles, solve, Answer, IntoAnswer, SolveError};
+
+    #[test]
+    fn every_day_registered() {
+        assert_eq!(
+            (1..=25).collect::<Vec<u8>>(),
+            puzzles().iter().map(|p| p.day).collect::<Vec<_>>()
+        );
+        assert_eq!(49, puzzles().iter().map(|p| p.parts().len()).sum::<usize>());
+    }
+
+    #[test]
+    fn integer_answers() {
+        assert_eq!(Ok(Answer::Integer(-3)), solve(1, 1, ")())())\n"));
+        assert_eq!(Ok(Answer::Integer(5)), solve(1, 2, "()())"));
+        assert_eq!(Ok(Answer::Integer(58)), solve(2, 1, "2x3x4\n"));
+    }
+
+    #[test]
+    fn string_answers() {
+        assert_eq!(
+            Ok(Answer::String("abcdffaa".to_string())),
+            solve(11, 1, "abcdefgh")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Err(SolveError::UnknownPuzzle { day: 25, part: 2 }),
+            solve(25, 2, "")
+        );
+        assert_eq!(
+            Err(SolveError::UnknownPuzzle { day: 26, part: 1 }),
+            solve(26, 1, "")
+        );
+        assert!(matches!(solve(2, 1, "2x3"), Err(SolveError::Parse(_))));
+        assert!(matches!(
+            solve(7, 1, "a -> b\nb -> a"),
+            Err(SolveError::Circuit(_))
+        ));
+    }
+
+    #[test]
+    fn malformed_raw_input() {
+        assert_eq!(
+            Err(SolveError::Parse(ParseError::new(1, 1, 2, "`(` or `)`"))),
+            solve(1, 1, "(x")
+        );
+        assert_eq!(Err(SolveError::NoAnswer), solve(1, 2, "(()"));
+        assert!(matches!(solve(12, 1, "{"), Err(SolveError::Parse(_))));
+        assert!(matches!(solve(20, 1, "many"), Err(SolveError::Parse(_))));
+    }
+
+    #[test]
+    fn answers_too_large() {
+        assert_eq!(
+            Ok(Answer::Integer(i64::MAX)),
+            (i64::MAX as u64).into_answer()
+        );
+        assert_eq!(
+            Err(SolveError::TooLarge(u64::MAX.to_string())),
+            u64::MAX.into_answer()
+        );
+    }
+}
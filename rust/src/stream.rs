@@ -0,0 +1,41 @@
+use std::io::{self, BufRead};
+use std::ops::ControlFlow;
+
+/// Feeds every byte of `reader` to `f` one buffer at a time, stopping early when `f` breaks.
+pub(crate) fn try_for_each_byte<B>(
+    mut reader: impl BufRead,
+    mut f: impl FnMut(u8) -> io::Result<ControlFlow<B>>,
+) -> io::Result<Option<B>> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(None);
+        }
+        let length = buffer.len();
+        for &byte in buffer {
+            if let ControlFlow::Break(value) = f(byte)? {
+                return Ok(Some(value));
+            }
+        }
+        reader.consume(length);
+    }
+}
+
+/// Feeds every line of `reader` to `f` without its line ending, reusing a single buffer.
+pub(crate) fn for_each_line(mut reader: impl BufRead, mut f: impl FnMut(&str)) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        f(line.trim_end_matches(['\n', '\r']));
+    }
+}
+
+pub(crate) fn invalid_byte(byte: u8, position: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected byte {byte:#04x} at position {position}"),
+    )
+}
@@ -0,0 +1,132 @@
+//! Regression tests against real puzzle answers.
+//!
+//! Inputs are personal and are not committed, so every test looks for `inputs/dayN.txt` (or
+//! `input/2015/dayN.txt`, where cargo-aoc and the CLI keep them) and `answers/dayN.txt` under
+//! `$AOC_2015_DATA` (defaulting to the crate root) and passes with a note on stderr when either
+//! file is absent. Set `AOC_2015_REQUIRE_INPUTS` in CI, where the inputs are expected, to turn a
+//! missing file into a failure.
+
+use advent_of_code_2015::answers::AnswerKey;
+use advent_of_code_2015::solve;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+fn data_dir() -> PathBuf {
+    env::var_os("AOC_2015_DATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")))
+}
+
+/// Input layouts tried in order, relative to [data_dir].
+const INPUT_DIRS: [&str; 2] = ["inputs", "input/2015"];
+
+fn input_path(dir: &Path, day: u8) -> PathBuf {
+    let file = format!("day{day}.txt");
+    INPUT_DIRS
+        .iter()
+        .map(|sub| dir.join(sub).join(&file))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| dir.join(INPUT_DIRS[0]).join(&file))
+}
+
+/// Skip the test, or fail it when `$AOC_2015_REQUIRE_INPUTS` says the files should be there.
+fn missing(day: u8, part: u8, reason: String) {
+    if env::var_os("AOC_2015_REQUIRE_INPUTS").is_some() {
+        panic!("day {day} part {part}: {reason}");
+    }
+    eprintln!("skipping day {day} part {part}: {reason}");
+}
+
+fn check(day: u8, part: u8) {
+    let dir = data_dir();
+    let input_path = input_path(&dir, day);
+    let answers_path = dir.join("answers").join(format!("day{day}.txt"));
+    let (Ok(input), Ok(answers)) = (
+        fs::read_to_string(&input_path),
+        fs::read_to_string(&answers_path),
+    ) else {
+        missing(
+            day,
+            part,
+            format!(
+                "need {} and {}",
+                input_path.display(),
+                answers_path.display()
+            ),
+        );
+        return;
+    };
+    let key = AnswerKey::parse(day, &answers).unwrap();
+    let Some(expected) = key.get(part) else {
+        missing(
+            day,
+            part,
+            format!("no answer in {}", answers_path.display()),
+        );
+        return;
+    };
+    assert_eq!(Ok(expected.clone()), solve(day, part, &input));
+}
+
+macro_rules! answers {
+    ($($name:ident: $day:literal, $part:literal;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check($day, $part);
+            }
+        )*
+    };
+}
+
+answers! {
+    day1_part1: 1, 1;
+    day1_part2: 1, 2;
+    day2_part1: 2, 1;
+    day2_part2: 2, 2;
+    day3_part1: 3, 1;
+    day3_part2: 3, 2;
+    day4_part1: 4, 1;
+    day4_part2: 4, 2;
+    day5_part1: 5, 1;
+    day5_part2: 5, 2;
+    day6_part1: 6, 1;
+    day6_part2: 6, 2;
+    day7_part1: 7, 1;
+    day7_part2: 7, 2;
+    day8_part1: 8, 1;
+    day8_part2: 8, 2;
+    day9_part1: 9, 1;
+    day9_part2: 9, 2;
+    day10_part1: 10, 1;
+    day10_part2: 10, 2;
+    day11_part1: 11, 1;
+    day11_part2: 11, 2;
+    day12_part1: 12, 1;
+    day12_part2: 12, 2;
+    day13_part1: 13, 1;
+    day13_part2: 13, 2;
+    day14_part1: 14, 1;
+    day14_part2: 14, 2;
+    day15_part1: 15, 1;
+    day15_part2: 15, 2;
+    day16_part1: 16, 1;
+    day16_part2: 16, 2;
+    day17_part1: 17, 1;
+    day17_part2: 17, 2;
+    day18_part1: 18, 1;
+    day18_part2: 18, 2;
+    day19_part1: 19, 1;
+    day19_part2: 19, 2;
+    day20_part1: 20, 1;
+    day20_part2: 20, 2;
+    day21_part1: 21, 1;
+    day21_part2: 21, 2;
+    day22_part1: 22, 1;
+    day22_part2: 22, 2;
+    day23_part1: 23, 1;
+    day23_part2: 23, 2;
+    day24_part1: 24, 1;
+    day24_part2: 24, 2;
+    day25_part1: 25, 1;
+}